@@ -0,0 +1,70 @@
+//! Compare sequential and parallel processing of many JSON files.
+//!
+//! Run with `cargo bench --bench multi_file`.
+
+use std::fs;
+use std::time::Instant;
+
+use i_edit_json::batch::process_files;
+use i_edit_json::get::{extract_field, ExtractConfig};
+use i_edit_json::set::{set_field, SetConfig};
+
+const FILES: usize = 2000;
+const KEYS_PER_FILE: usize = 200;
+
+fn time(label: &str, run: impl Fn()) {
+    run(); // warm up the page cache
+    let start = Instant::now();
+    run();
+    println!("{:<28} {:>10.2?}", label, start.elapsed());
+}
+
+fn main() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let files: Vec<String> = (0..FILES)
+        .map(|i| {
+            let mut object = serde_json::Map::new();
+            for key in 0..KEYS_PER_FILE {
+                object.insert(format!("key{}", key), format!("value {} {}", i, key).into());
+            }
+            object.insert("build".into(), serde_json::json!({ "number": i }));
+            let path = dir.path().join(format!("fixture{}.json", i));
+            fs::write(&path, serde_json::to_string_pretty(&object).unwrap()).unwrap();
+            path.to_string_lossy().into_owned()
+        })
+        .collect();
+
+    let get = |path: &str| {
+        extract_field(&ExtractConfig {
+            file_path: path.to_string(),
+            field_path: "build.number".to_string(),
+            ..Default::default()
+        })
+    };
+    let set = |path: &str| {
+        set_field(&SetConfig {
+            file_path: path.to_string(),
+            field_path: "build.number".to_string(),
+            value: "42".to_string(),
+            ..Default::default()
+        })
+    };
+
+    println!("{} files, {} keys each", FILES, KEYS_PER_FILE);
+    for jobs in [1, 2, 4, 0] {
+        let label = match jobs {
+            0 => "all cores".to_string(),
+            n => format!("{} job(s)", n),
+        };
+        time(&format!("get, {}", label), || {
+            assert!(process_files(&files, jobs, get)
+                .iter()
+                .all(|o| o.result.is_ok()));
+        });
+        time(&format!("set, {}", label), || {
+            assert!(process_files(&files, jobs, set)
+                .iter()
+                .all(|o| o.result.is_ok()));
+        });
+    }
+}
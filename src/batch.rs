@@ -0,0 +1,152 @@
+//! Running commands over multiple files
+//!
+//! File arguments may be repeated and may contain glob patterns; every matched
+//! file is processed independently so one failure does not stop the others.
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::JsonExtractError;
+
+/// Outcome of processing a single file
+#[derive(Debug)]
+pub struct FileOutcome<T> {
+    /// Path of the processed file
+    pub file: String,
+    /// Value produced for the file, or the error that occurred
+    pub result: Result<T>,
+}
+
+/// Whether a file argument is a glob pattern rather than a plain path
+pub fn is_glob_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Expand file arguments into a list of paths
+///
+/// Plain paths (including `-` for stdin) are kept as given; glob patterns are
+/// expanded in sorted order and must match at least one file. Duplicates are
+/// removed, keeping the first occurrence.
+pub fn expand_file_patterns(patterns: &[String]) -> Result<Vec<String>, JsonExtractError> {
+    let mut files: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for pattern in patterns {
+        let matched = if is_glob_pattern(pattern) {
+            let paths = glob::glob(pattern)
+                .map_err(|e| JsonExtractError::InvalidPattern(format!("{}: {}", pattern, e)))?;
+            let mut matched: Vec<String> = paths
+                .filter_map(|path| path.ok())
+                .filter(|path| path.is_file())
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            if matched.is_empty() {
+                return Err(JsonExtractError::FileNotFound(pattern.to_string()));
+            }
+            matched.sort();
+            matched
+        } else {
+            vec![pattern.to_string()]
+        };
+
+        for file in matched {
+            if seen.insert(file.clone()) {
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Apply `process` to every file, collecting outcomes in input order
+///
+/// With `jobs > 1`, files are distributed over a pool of worker threads; the
+/// returned outcomes still follow the order of `files`. `jobs == 0` uses the
+/// available parallelism of the machine.
+pub fn process_files<T, F>(files: &[String], jobs: usize, process: F) -> Vec<FileOutcome<T>>
+where
+    T: Send,
+    F: Fn(&str) -> Result<T> + Sync,
+{
+    let run = |file: &String| FileOutcome {
+        file: file.clone(),
+        result: process(file),
+    };
+
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    }
+    .min(files.len());
+    if jobs <= 1 {
+        return files.iter().map(run).collect();
+    }
+
+    // Workers pull the next file index from a shared counter
+    let next = AtomicUsize::new(0);
+    let mut slots: Vec<Option<FileOutcome<T>>> =
+        std::iter::repeat_with(|| None).take(files.len()).collect();
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(index) else {
+                            break done;
+                        };
+                        done.push((index, run(file)));
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (index, outcome) in worker.join().expect("worker thread panicked") {
+                slots[index] = Some(outcome);
+            }
+        }
+    });
+
+    slots.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_expand_file_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.json", "a.json", "c.txt"] {
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        let base = dir.path().to_string_lossy();
+
+        let files = expand_file_patterns(&[format!("{}/b.json", base), format!("{}/*.json", base)])
+            .unwrap();
+        assert_eq!(
+            files,
+            vec![format!("{}/b.json", base), format!("{}/a.json", base)]
+        );
+
+        assert!(matches!(
+            expand_file_patterns(&[format!("{}/*.yaml", base)]),
+            Err(JsonExtractError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_process_files_keeps_order() {
+        let files: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+        let outcomes = process_files(&files, 4, |file| Ok(file.parse::<usize>()? * 2));
+
+        let values: Vec<usize> = outcomes.into_iter().map(|o| o.result.unwrap()).collect();
+        assert_eq!(values, (0..50).map(|i| i * 2).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,207 @@
+//! Core functionality for bump command
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::path::{Path, PathBuf};
+
+use super::types::{BumpConfig, BumpResult};
+use super::utils::{bump_version_string, update_dependency_range, DEPENDENCY_SECTIONS};
+use crate::error::JsonExtractError;
+use crate::format::jsonc;
+use crate::get::{core::extract_field_from_str, types::ExtractConfig};
+use crate::set::utils::{get_nested_value_mut, split_field_path};
+use crate::source::{parse_json, read_source, write_output};
+
+/// Compute a version bump without writing any file
+///
+/// `updated_files` lists the files that would change.
+pub fn bump_version(config: &BumpConfig) -> Result<BumpResult> {
+    let (result, _) = plan_bump(config)?;
+    Ok(result)
+}
+
+/// Bump the version and save every affected file
+pub fn bump_version_and_save(config: &BumpConfig) -> Result<BumpResult> {
+    let (result, writes) = plan_bump(config)?;
+    for (path, content) in writes {
+        write_output(&path, &content)?;
+    }
+    Ok(result)
+}
+
+/// Compute the new version and the updated content of each affected file
+fn plan_bump(config: &BumpConfig) -> Result<(BumpResult, Vec<(String, String)>)> {
+    let (content, mut root) = read_manifest(&config.file_path)?;
+    let old_version = extract_field_from_str(
+        &content,
+        &ExtractConfig {
+            file_path: config.file_path.clone(),
+            field_path: config.field_path.clone(),
+            output_format: None,
+            strip_quotes: true,
+            format: None,
+        },
+    )?;
+    let new_version = bump_version_string(
+        &old_version,
+        config.level,
+        config.preid.as_deref(),
+        config.build.as_deref(),
+    )?;
+
+    for field_path in std::iter::once(&config.field_path).chain(&config.also) {
+        let parts = split_field_path(field_path)?;
+        *get_nested_value_mut(&mut root, &parts)? = JsonValue::String(new_version.clone());
+    }
+
+    let mut manifests = Vec::new();
+    if config.workspace {
+        for path in workspace_manifests(&config.file_path, &root)? {
+            let (content, mut value) = read_manifest(&path)?;
+            let parts = split_field_path(&config.field_path)?;
+            if let Ok(version) = get_nested_value_mut(&mut value, &parts) {
+                *version = JsonValue::String(new_version.clone());
+            }
+            manifests.push((path, content, value));
+        }
+
+        let names: Vec<String> = std::iter::once(&root)
+            .chain(manifests.iter().map(|(_, _, value)| value))
+            .filter_map(|value| value.get("name").and_then(JsonValue::as_str))
+            .map(str::to_string)
+            .collect();
+        update_dependencies(&mut root, &names, &new_version);
+        for (_, _, value) in manifests.iter_mut() {
+            update_dependencies(value, &names, &new_version);
+        }
+    }
+    manifests.insert(0, (config.file_path.clone(), content, root));
+
+    let mut writes = Vec::new();
+    for (path, content, value) in manifests {
+        // Patch only the changed tokens, keeping inline arrays and objects
+        let updated = jsonc::write(&content, &value)?;
+        if updated != content {
+            writes.push((path, updated));
+        }
+    }
+
+    let result = BumpResult {
+        old_version,
+        new_version,
+        updated_files: writes.iter().map(|(path, _)| path.clone()).collect(),
+    };
+    Ok((result, writes))
+}
+
+/// Read a JSON file, returning its raw content alongside the parsed value
+fn read_manifest(file_path: &str) -> Result<(String, JsonValue)> {
+    let content = read_source(file_path)?;
+    let value = parse_json(&content, file_path)?;
+    Ok((content, value))
+}
+
+/// Resolve package.json files of the workspaces declared in a root manifest
+///
+/// Supports both `"workspaces": [..]` and `"workspaces": {"packages": [..]}`,
+/// including `!`-prefixed exclusion patterns.
+fn workspace_manifests(root_path: &str, root: &JsonValue) -> Result<Vec<String>> {
+    let workspaces = root
+        .get("workspaces")
+        .ok_or_else(|| JsonExtractError::FieldNotFound("workspaces".to_string()))?;
+    let patterns = workspaces
+        .get("packages")
+        .unwrap_or(workspaces)
+        .as_array()
+        .ok_or_else(|| JsonExtractError::NotAnArray("workspaces".to_string()))?;
+
+    let root_dir = Path::new(root_path)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let expand = |pattern: &str| -> Result<Vec<PathBuf>> {
+        let pattern = root_dir.join(pattern).join("package.json");
+        let pattern = pattern.to_string_lossy();
+        let paths = glob::glob(&pattern)
+            .map_err(|e| JsonExtractError::InvalidFieldPath(format!("{}: {}", pattern, e)))?;
+        Ok(paths.filter_map(Result::ok).collect())
+    };
+
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for pattern in patterns.iter().filter_map(JsonValue::as_str) {
+        match pattern.strip_prefix('!') {
+            Some(pattern) => excluded.extend(expand(pattern)?),
+            None => included.extend(expand(pattern)?),
+        }
+    }
+
+    let mut manifests: Vec<String> = included
+        .into_iter()
+        .filter(|path| !excluded.contains(path))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    manifests.sort();
+    manifests.dedup();
+    Ok(manifests)
+}
+
+/// Point dependency entries on any of `names` at the new version
+fn update_dependencies(manifest: &mut JsonValue, names: &[String], new_version: &str) {
+    for section in DEPENDENCY_SECTIONS {
+        let Some(deps) = manifest.get_mut(section).and_then(JsonValue::as_object_mut) else {
+            continue;
+        };
+        for name in names {
+            if let Some(JsonValue::String(range)) = deps.get_mut(name) {
+                if let Some(updated) = update_dependency_range(range, new_version) {
+                    *range = updated;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bump::types::BumpLevel;
+    use std::fs;
+
+    #[test]
+    fn test_bump_version_with_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("package.json");
+        let root_original = "{\n    \"name\": \"root\",\n    \"version\": \"1.2.3\",\n    \"files\": [\"dist\", \"README.md\"],\n    \"config\": {\"version\": \"1.2.3\"},\n    \"workspaces\": [\"packages/*\"]\n}\n";
+        fs::write(&root, root_original).unwrap();
+        fs::create_dir_all(dir.path().join("packages/a")).unwrap();
+        let member = dir.path().join("packages/a/package.json");
+        fs::write(
+            &member,
+            r#"{"name":"a","version":"1.2.3","dependencies":{"root":"^1.2.3","left-pad":"^1.0.0"}}"#,
+        )
+        .unwrap();
+
+        let config = BumpConfig {
+            file_path: root.to_string_lossy().into_owned(),
+            level: BumpLevel::Minor,
+            also: vec!["config.version".to_string()],
+            workspace: true,
+            ..Default::default()
+        };
+        let result = bump_version_and_save(&config).unwrap();
+        assert_eq!(result.old_version, "1.2.3");
+        assert_eq!(result.new_version, "1.3.0");
+        assert_eq!(result.updated_files.len(), 2);
+
+        // Only the version tokens change, byte for byte
+        assert_eq!(
+            fs::read_to_string(&root).unwrap(),
+            root_original.replace("1.2.3", "1.3.0")
+        );
+        assert_eq!(
+            fs::read_to_string(&member).unwrap(),
+            r#"{"name":"a","version":"1.3.0","dependencies":{"root":"^1.3.0","left-pad":"^1.0.0"}}"#
+        );
+    }
+}
@@ -0,0 +1,14 @@
+//! Semantic version bumping functionality
+//!
+//! Provides utilities for incrementing semver fields in JSON files, keeping
+//! dependent fields and workspace packages in sync.
+
+pub mod core;
+pub mod types;
+pub mod utils;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use utils::*;
+pub use xcli::*;
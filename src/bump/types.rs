@@ -0,0 +1,75 @@
+//! Type definitions for bump command
+
+/// Part of a semantic version to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BumpLevel {
+    /// 1.2.3 -> 2.0.0
+    Major,
+    /// 1.2.3 -> 1.3.0
+    Minor,
+    /// 1.2.3 -> 1.2.4
+    Patch,
+    /// 1.2.3 -> 1.2.4-0, 1.2.4-beta.0 -> 1.2.4-beta.1
+    Prerelease,
+    /// 1.2.3+build.1 -> 1.2.3+build.2
+    Build,
+}
+
+impl std::str::FromStr for BumpLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" => Ok(Self::Major),
+            "minor" => Ok(Self::Minor),
+            "patch" => Ok(Self::Patch),
+            "prerelease" => Ok(Self::Prerelease),
+            "build" => Ok(Self::Build),
+            _ => Err(format!("Unknown bump level: {}", s)),
+        }
+    }
+}
+
+/// Configuration for version bumping
+#[derive(Debug, Clone)]
+pub struct BumpConfig {
+    /// Path to the JSON file
+    pub file_path: String,
+    /// Dot-separated path to the version field
+    pub field_path: String,
+    /// Part of the version to increment
+    pub level: BumpLevel,
+    /// Prerelease identifier (e.g., "beta") used by prerelease bumps
+    pub preid: Option<String>,
+    /// Explicit build metadata used by build bumps
+    pub build: Option<String>,
+    /// Additional fields in the same file set to the new version
+    pub also: Vec<String>,
+    /// Whether to update workspace package.json files as well
+    pub workspace: bool,
+}
+
+impl Default for BumpConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: "version".to_string(),
+            level: BumpLevel::Patch,
+            preid: None,
+            build: None,
+            also: Vec::new(),
+            workspace: false,
+        }
+    }
+}
+
+/// Outcome of a version bump
+#[derive(Debug, Clone)]
+pub struct BumpResult {
+    /// Version before the bump
+    pub old_version: String,
+    /// Version after the bump
+    pub new_version: String,
+    /// Files whose content changed, in the order they were written
+    pub updated_files: Vec<String>,
+}
@@ -0,0 +1,172 @@
+//! Utility functions for bump command
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+use super::types::BumpLevel;
+use crate::error::JsonExtractError;
+
+/// Dependency sections updated when bumping workspace packages
+pub const DEPENDENCY_SECTIONS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Parse a semantic version, mapping failures to `InvalidValueType`
+pub fn parse_version(version: &str) -> Result<Version, JsonExtractError> {
+    Version::parse(version.trim()).map_err(|e| {
+        JsonExtractError::InvalidValueType(format!(
+            "{} is not a valid semantic version: {}",
+            version, e
+        ))
+    })
+}
+
+/// Increment a semantic version string
+///
+/// Follows npm semantics: bumping a prerelease to its release level drops the
+/// prerelease instead of incrementing (1.3.0-beta.2 --minor--> 1.3.0).
+pub fn bump_version_string(
+    version: &str,
+    level: BumpLevel,
+    preid: Option<&str>,
+    build: Option<&str>,
+) -> Result<String, JsonExtractError> {
+    let mut version = parse_version(version)?;
+    let is_prerelease = !version.pre.is_empty();
+
+    match level {
+        BumpLevel::Major => {
+            if !(is_prerelease && version.minor == 0 && version.patch == 0) {
+                version.major += 1;
+            }
+            version.minor = 0;
+            version.patch = 0;
+            version.pre = Prerelease::EMPTY;
+        }
+        BumpLevel::Minor => {
+            if !(is_prerelease && version.patch == 0) {
+                version.minor += 1;
+            }
+            version.patch = 0;
+            version.pre = Prerelease::EMPTY;
+        }
+        BumpLevel::Patch => {
+            if !is_prerelease {
+                version.patch += 1;
+            }
+            version.pre = Prerelease::EMPTY;
+        }
+        BumpLevel::Prerelease => {
+            if !is_prerelease {
+                version.patch += 1;
+            }
+            version.pre = new_prerelease(version.pre.as_str(), preid)?;
+        }
+        BumpLevel::Build => {
+            let metadata = match build {
+                Some(build) => build.to_string(),
+                None => increment_identifiers(version.build.as_str()),
+            };
+            version.build = BuildMetadata::new(&metadata).map_err(|e| {
+                JsonExtractError::InvalidValueType(format!(
+                    "{} is not valid build metadata: {}",
+                    metadata, e
+                ))
+            })?;
+            return Ok(version.to_string());
+        }
+    }
+
+    version.build = BuildMetadata::EMPTY;
+    Ok(version.to_string())
+}
+
+/// Compute the next prerelease, switching identifiers when `preid` changes
+fn new_prerelease(current: &str, preid: Option<&str>) -> Result<Prerelease, JsonExtractError> {
+    let next = match preid {
+        Some(id) if current.split('.').next() != Some(id) => format!("{}.0", id),
+        None if current.is_empty() => "0".to_string(),
+        _ => increment_identifiers(current),
+    };
+
+    Prerelease::new(&next).map_err(|e| {
+        JsonExtractError::InvalidValueType(format!("{} is not a valid prerelease: {}", next, e))
+    })
+}
+
+/// Increment the last numeric dot-separated identifier, appending one if absent
+fn increment_identifiers(identifiers: &str) -> String {
+    if identifiers.is_empty() {
+        return "1".to_string();
+    }
+
+    let mut parts: Vec<String> = identifiers.split('.').map(str::to_string).collect();
+    match parts.iter().rposition(|p| p.parse::<u64>().is_ok()) {
+        Some(pos) => {
+            let number = parts[pos].parse::<u64>().unwrap();
+            parts[pos] = (number + 1).to_string();
+        }
+        None => parts.push("0".to_string()),
+    }
+    parts.join(".")
+}
+
+/// Rewrite a dependency range to point at a new version, keeping its prefix
+///
+/// Returns `None` for ranges that are not a plain version with an optional
+/// operator prefix (e.g., `*`, `workspace:*`, `file:../pkg`, `1.x`).
+pub fn update_dependency_range(range: &str, new_version: &str) -> Option<String> {
+    let start = range.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, version) = range.split_at(start);
+
+    let allowed_prefix = matches!(
+        prefix.trim_start_matches("workspace:"),
+        "" | "^" | "~" | "=" | ">=" | "v"
+    );
+    if allowed_prefix && Version::parse(version).is_ok() {
+        Some(format!("{}{}", prefix, new_version))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_version_string() {
+        let bump = |v, level| bump_version_string(v, level, None, None).unwrap();
+
+        assert_eq!(bump("1.2.3", BumpLevel::Major), "2.0.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3+abc", BumpLevel::Patch), "1.2.4");
+        assert_eq!(bump("1.3.0-beta.2", BumpLevel::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Prerelease), "1.2.4-0");
+        assert_eq!(bump("1.2.4-beta.1", BumpLevel::Prerelease), "1.2.4-beta.2");
+        assert_eq!(bump("1.2.3+build.7", BumpLevel::Build), "1.2.3+build.8");
+        assert_eq!(
+            bump_version_string("1.2.4-alpha.3", BumpLevel::Prerelease, Some("beta"), None)
+                .unwrap(),
+            "1.2.4-beta.0"
+        );
+        assert!(bump_version_string("1.2", BumpLevel::Patch, None, None).is_err());
+        assert!(bump_version_string("1.2.3", BumpLevel::Build, None, Some("a..b")).is_err());
+    }
+
+    #[test]
+    fn test_update_dependency_range() {
+        assert_eq!(
+            update_dependency_range("^1.2.3", "1.3.0"),
+            Some("^1.3.0".to_string())
+        );
+        assert_eq!(
+            update_dependency_range("workspace:~1.2.3", "1.3.0"),
+            Some("workspace:~1.3.0".to_string())
+        );
+        assert_eq!(update_dependency_range("workspace:*", "1.3.0"), None);
+        assert_eq!(update_dependency_range("file:../pkg", "1.3.0"), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! CLI definitions and handling for bump command
+
+use anyhow::Result;
+use clap::{Arg, Command};
+
+use super::core::{bump_version, bump_version_and_save};
+use super::types::{BumpConfig, BumpLevel};
+use crate::source::STDIO_PATH;
+
+/// Define the bump command CLI structure
+pub fn bump_command() -> Command {
+    Command::new("bump")
+        .about("Bump a semantic version field")
+        .arg(
+            Arg::new("level")
+                .value_name("LEVEL")
+                .help("Version part to increment")
+                .value_parser(["major", "minor", "patch", "prerelease", "build"])
+                .required(true),
+        )
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("JSON file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Dot-separated path to the version field")
+                .default_value("version"),
+        )
+        .arg(
+            Arg::new("preid")
+                .long("preid")
+                .value_name("ID")
+                .help("Prerelease identifier for prerelease bumps (e.g., alpha, beta, rc)"),
+        )
+        .arg(
+            Arg::new("build")
+                .long("build")
+                .value_name("METADATA")
+                .help("Build metadata to set for build bumps (default: increment existing)"),
+        )
+        .arg(
+            Arg::new("also")
+                .long("also")
+                .value_name("FIELD")
+                .action(clap::ArgAction::Append)
+                .help("Also set this field to the new version (can be used multiple times)"),
+        )
+        .arg(
+            Arg::new("workspace")
+                .long("workspace")
+                .help("Also bump workspace packages and their internal dependency ranges")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("in-place")
+                .short('i')
+                .long("in-place")
+                .help("Write the new version to the file(s) instead of only printing it")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Handle bump command logic
+pub fn handle_bump_command(matches: &clap::ArgMatches) -> Result<()> {
+    let level: BumpLevel = matches
+        .get_one::<String>("level")
+        .unwrap()
+        .parse()
+        .map_err(anyhow::Error::msg)?;
+
+    let config = BumpConfig {
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        field_path: matches.get_one::<String>("field").unwrap().to_string(),
+        level,
+        preid: matches.get_one::<String>("preid").cloned(),
+        build: matches.get_one::<String>("build").cloned(),
+        also: matches
+            .get_many::<String>("also")
+            .map(|fields| fields.cloned().collect())
+            .unwrap_or_default(),
+        workspace: matches.get_flag("workspace"),
+    };
+
+    if matches.get_flag("in-place") {
+        // Stdin input is written back to stdout, so skip the summary there
+        let result = bump_version_and_save(&config)?;
+        for file in result.updated_files.iter().filter(|f| *f != STDIO_PATH) {
+            println!(
+                "✅ Version bumped from '{}' to '{}' in {}",
+                result.old_version, result.new_version, file
+            );
+        }
+    } else {
+        println!("{}", bump_version(&config)?.new_version);
+    }
+
+    Ok(())
+}
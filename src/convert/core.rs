@@ -0,0 +1,150 @@
+//! Core functionality for convert command
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+
+use super::types::{ConvertConfig, Target};
+use crate::format::{json5, Format};
+use crate::get::utils::{format_output, get_nested_value};
+use crate::ndjson;
+use crate::source::read_source;
+
+/// Convert a file (`-` reads stdin) and return the converted text
+pub fn convert(config: &ConvertConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    convert_str(&content, config)
+}
+
+/// Convert document text; `config.file_path` names the input in error
+/// messages and selects its format when none is given
+pub fn convert_str(content: &str, config: &ConvertConfig) -> Result<String> {
+    let value = if config.ndjson {
+        JsonValue::Array(
+            ndjson::records(content.as_bytes())
+                .map(|record| Ok(record?.parse(&config.file_path)?))
+                .collect::<Result<_>>()?,
+        )
+    } else {
+        Format::resolve(config.format, &config.file_path).parse(content, &config.file_path)?
+    };
+
+    let value = match &config.field_path {
+        Some(path) => {
+            get_nested_value(&value, path).context(format!("Field not found: {}", path))?
+        }
+        None => &value,
+    };
+    convert_value(value, config.target)
+}
+
+/// Serialize a value in the target syntax, without a trailing line break
+///
+/// Fails when the target cannot represent the value, e.g. null in TOML or
+/// a JSON5 `Infinity` in anything but JSON5.
+pub fn convert_value(value: &JsonValue, target: Target) -> Result<String> {
+    if target != Target::Document(Format::Json5) {
+        json5::check_finite(value)?;
+    }
+    let output = match target {
+        Target::Json => format_output(value, Some("json-pretty"))?,
+        Target::JsonMin => format_output(value, Some("json"))?,
+        Target::Ndjson => match value {
+            JsonValue::Array(items) => items
+                .iter()
+                .map(|item| format_output(item, Some("json")))
+                .collect::<Result<Vec<_>>>()?
+                .join("\n"),
+            other => format_output(other, Some("json"))?,
+        },
+        Target::Document(format) => format
+            .emit(value)
+            .with_context(|| format!("Cannot convert to {}", format.name()))?,
+    };
+    Ok(output.trim_end_matches('\n').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = "services:\n  web:\n    image: nginx\n    ports: [80, 443]\n";
+
+    #[test]
+    fn test_convert_yaml_subtree_to_toml() {
+        let config = ConvertConfig {
+            file_path: "compose.yaml".to_string(),
+            field_path: Some("services".to_string()),
+            target: "toml".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_str(COMPOSE, &config).unwrap(),
+            "[web]\nimage = \"nginx\"\nports = [80, 443]"
+        );
+
+        let error = convert_str(
+            "{\"a\": {\"b\": null}}",
+            &ConvertConfig {
+                file_path: "a.json".to_string(),
+                field_path: None,
+                ..config
+            },
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", error).contains("TOML has no null value at a.b"));
+    }
+
+    #[test]
+    fn test_convert_ndjson() {
+        let config = ConvertConfig {
+            file_path: "events.jsonl".to_string(),
+            ndjson: true,
+            target: Target::JsonMin,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_str("{\"id\": 1}\n\n{\"id\": 2}\n", &config).unwrap(),
+            "[{\"id\":1},{\"id\":2}]"
+        );
+        assert_eq!(
+            convert_value(&serde_json::json!([1, {"a": true}]), Target::Ndjson).unwrap(),
+            "1\n{\"a\":true}"
+        );
+    }
+
+    #[test]
+    fn test_convert_json5_infinity() {
+        let content = "{\n  name: 'demo',\n  max: +Infinity,\n}\n";
+        let config = ConvertConfig {
+            file_path: "x.json5".to_string(),
+            field_path: Some("name".to_string()),
+            target: Target::Json,
+            ..Default::default()
+        };
+        assert_eq!(convert_str(content, &config).unwrap(), "\"demo\"");
+
+        for (field_path, target) in [
+            (Some("max".to_string()), Target::Json),
+            (None, Target::Json),
+            (None, "yaml".parse().unwrap()),
+        ] {
+            let config = ConvertConfig {
+                field_path,
+                target,
+                ..config.clone()
+            };
+            let error = convert_str(content, &config).unwrap_err();
+            assert_eq!(crate::error::exit_code(&error), 5);
+        }
+
+        let config = ConvertConfig {
+            field_path: None,
+            target: "json5".parse().unwrap(),
+            ..config
+        };
+        assert_eq!(
+            convert_str(content, &config).unwrap(),
+            "{\n  name: 'demo',\n  max: +Infinity\n}"
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Format conversion functionality
+//!
+//! Reads a document in any supported format, optionally narrows it to a
+//! sub-tree, and writes it out as JSON, JSONC, JSON5, YAML, TOML or NDJSON.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
@@ -0,0 +1,56 @@
+//! Type definitions for convert command
+
+use crate::format::Format;
+
+/// Output syntax of a conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// Pretty-printed JSON
+    Json,
+    /// JSON on a single line
+    JsonMin,
+    /// JSON Lines: one compact value per line, one line per array element
+    Ndjson,
+    /// A document format with its own serializer (JSONC, JSON5, YAML, TOML)
+    Document(Format),
+}
+
+impl std::str::FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" | "json-pretty" => Ok(Self::Json),
+            "json-min" => Ok(Self::JsonMin),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            other => other.parse().map(Self::Document),
+        }
+    }
+}
+
+/// Configuration for format conversion
+#[derive(Debug, Clone)]
+pub struct ConvertConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// Read the input as JSON Lines; the records become an array
+    pub ndjson: bool,
+    /// Dot-separated path of the sub-tree to convert (None for the whole document)
+    pub field_path: Option<String>,
+    /// Output syntax
+    pub target: Target,
+}
+
+impl Default for ConvertConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            format: None,
+            ndjson: false,
+            field_path: None,
+            target: Target::Json,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! CLI definitions and handling for convert command
+
+use anyhow::Result;
+use clap::{Arg, Command};
+
+use super::core::convert;
+use super::types::{ConvertConfig, Target};
+use crate::format::Format;
+use crate::source::{write_output, STDIO_PATH};
+
+/// Define the convert command CLI structure
+pub fn convert_command() -> Command {
+    Command::new("convert")
+        .about("Convert a document between JSON, JSONC, JSON5, YAML, TOML and NDJSON")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("Input file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Convert only the value at this dot-separated path"),
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .value_name("FORMAT")
+                .help("Input format (default: detected from the file name)")
+                .value_parser([
+                    "json", "jsonc", "json5", "yaml", "yml", "toml", "ndjson", "jsonl",
+                ]),
+        )
+        .arg(
+            Arg::new("to")
+                .short('t')
+                .long("to")
+                .value_name("FORMAT")
+                .help("Output format (json is pretty-printed, json-min is a single line)")
+                .value_parser([
+                    "json", "json-min", "jsonc", "json5", "yaml", "yml", "toml", "ndjson", "jsonl",
+                ])
+                .required(true),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Output file path ('-' for stdout)")
+                .default_value(STDIO_PATH),
+        )
+}
+
+/// Handle convert command logic
+pub fn handle_convert_command(matches: &clap::ArgMatches) -> Result<()> {
+    let from = matches.get_one::<String>("from").map(String::as_str);
+    let ndjson = matches!(from, Some("ndjson" | "jsonl"));
+    let format = match from {
+        Some(name) if !ndjson => Some(name.parse::<Format>().map_err(anyhow::Error::msg)?),
+        _ => None,
+    };
+    let to = matches.get_one::<String>("to").unwrap();
+
+    let config = ConvertConfig {
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        format,
+        ndjson,
+        field_path: matches.get_one::<String>("field").cloned(),
+        target: to.parse::<Target>().map_err(anyhow::Error::msg)?,
+    };
+    let output = convert(&config)?;
+
+    let path = matches.get_one::<String>("output").unwrap();
+    if path == STDIO_PATH {
+        write_output(path, &output)?;
+    } else {
+        write_output(path, &format!("{}\n", output))?;
+        println!("✅ Converted {} to {} in {}", config.file_path, to, path);
+    }
+
+    Ok(())
+}
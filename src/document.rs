@@ -0,0 +1,54 @@
+//! Loading documents and selecting the part a command works on
+//!
+//! Read-only commands parse their input in the format given on the command
+//! line (or detected from the file name) and then work either on the whole
+//! document or on the value at a field path.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+
+use crate::format::{json5, Format};
+use crate::get::utils::get_nested_value;
+
+/// Parse document text in `format`, or in the format detected from `path`
+pub fn parse(content: &str, format: Option<Format>, path: &str) -> Result<JsonValue> {
+    Ok(Format::resolve(format, path).parse(content, path)?)
+}
+
+/// The value at `field_path`, or the whole document when there is none
+///
+/// Fails when the selected value holds a JSON5 `Infinity` or `NaN`.
+pub fn select<'a>(value: &'a JsonValue, field_path: Option<&str>) -> Result<&'a JsonValue> {
+    match field_path {
+        Some(path) => {
+            Ok(get_nested_value(value, path).context(format!("Field not found: {}", path))?)
+        }
+        None => {
+            json5::check_finite(value)?;
+            Ok(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::exit_code;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_select() {
+        let value = parse("a: {b: 1}\n", None, "config.yaml").unwrap();
+        assert_eq!(value, json!({"a": {"b": 1}}));
+        assert_eq!(select(&value, Some("a.b")).unwrap(), &json!(1));
+        assert_eq!(select(&value, None).unwrap(), &value);
+
+        let missing = select(&value, Some("a.c")).unwrap_err();
+        assert_eq!(missing.to_string(), "Field not found: a.c");
+        assert_eq!(exit_code(&missing), 4);
+
+        let value = parse("{a: 1, b: NaN}", Some(Format::Json5), "-").unwrap();
+        assert!(select(&value, Some("a")).is_ok());
+        assert_eq!(exit_code(&select(&value, None).unwrap_err()), 5);
+    }
+}
@@ -0,0 +1,208 @@
+//! Core functionality for env command
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+
+use super::types::{EnvConfig, EnvStyle, NameCase};
+use crate::document;
+use crate::error::JsonExtractError;
+use crate::get::utils::{
+    dotenv_quote, env_leaves, get_nested_value, shell_quote, unique_names, value_type_name,
+    variable_name,
+};
+use crate::source::read_source;
+
+/// Export the fields of a file (`-` reads stdin) as variable lines
+pub fn export_env(config: &EnvConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    export_env_from_str(&content, config)
+}
+
+/// Export the fields of document text as variable lines
+pub fn export_env_from_str(content: &str, config: &EnvConfig) -> Result<String> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    Ok(render_variables(&env_pairs(&value, config)?, config.style))
+}
+
+/// `(NAME, text)` pairs for the fields selected by `config`, in order
+///
+/// Strings are exported as they are, null as an empty value and other
+/// scalars as JSON. Fails when two fields end up with the same name.
+pub fn env_pairs(value: &JsonValue, config: &EnvConfig) -> Result<Vec<(String, String)>> {
+    let cased = |name: &str| match config.case {
+        NameCase::Upper => name.to_ascii_uppercase(),
+        NameCase::Lower => name.to_ascii_lowercase(),
+        NameCase::Preserve => name.to_string(),
+    };
+    let name = |path: &str| format!("{}{}", config.prefix, cased(&variable_name(path)));
+    let join = |base: &str, leaf: &str| match (base.is_empty(), leaf.is_empty()) {
+        (_, true) => base.to_string(),
+        (true, false) => leaf.to_string(),
+        (false, false) => format!("{}_{}", base, leaf),
+    };
+
+    if config.fields.is_empty() {
+        let tree = document::select(value, config.field_path.as_deref())?;
+        if !tree.is_object() && !tree.is_array() {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "variable output needs an object or array, not {} (use -m NAME=PATH to export a single value)",
+                value_type_name(tree)
+            ))
+            .into());
+        }
+        let path = config.field_path.as_deref().unwrap_or_default();
+        let leaves = env_leaves(tree, path)
+            .into_iter()
+            .map(|(leaf, path, text)| (name(&leaf), path, text));
+        return Ok(unique_names(leaves)?);
+    }
+
+    let mut leaves = Vec::new();
+    for spec in &config.fields {
+        let (base, path) = split_field(spec);
+        let field = get_nested_value(value, path).context(format!("Field not found: {}", path))?;
+        for (leaf, leaf_path, text) in env_leaves(field, path) {
+            let name = match base {
+                // A name given as NAME= keeps its case; only the parts
+                // derived from the keys below it follow --case
+                Some(base) => {
+                    let name = variable_name(&join(base, &leaf));
+                    format!("{}{}{}", config.prefix, base, cased(&name[base.len()..]))
+                }
+                None => name(&join(path, &leaf)),
+            };
+            leaves.push((name, leaf_path, text));
+        }
+    }
+    Ok(unique_names(leaves)?)
+}
+
+/// Split `NAME=PATH` into its parts; a plain `PATH` has no name
+///
+/// Only a valid variable name before the first `=` counts as a name, so paths
+/// with `=` in quoted keys are kept whole.
+fn split_field(spec: &str) -> (Option<&str>, &str) {
+    match spec.split_once('=') {
+        Some((name, path))
+            if !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            (Some(name), path)
+        }
+        _ => (None, spec),
+    }
+}
+
+/// Variable lines in the given style
+pub fn render_variables(pairs: &[(String, String)], style: EnvStyle) -> String {
+    pairs
+        .iter()
+        .map(|(name, text)| match style {
+            EnvStyle::Shell => format!("export {}={}", name, shell_quote(text)),
+            EnvStyle::Dotenv => format!("{}={}", name, dotenv_quote(text)),
+            EnvStyle::Github => github_variable(name, text),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A variable in the GitHub Actions environment file syntax
+///
+/// Multi-line values use the `NAME<<DELIMITER` form with a delimiter that
+/// does not occur in the value.
+fn github_variable(name: &str, text: &str) -> String {
+    if !text.contains(['\n', '\r']) {
+        return format!("{}={}", name, text);
+    }
+    let mut delimiter = "EOF".to_string();
+    while text.contains(&delimiter) {
+        delimiter.push('_');
+    }
+    format!("{}<<{}\n{}\n{}", name, delimiter, text, delimiter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "name": "demo",
+        "version": "1.0.0",
+        "database": {"host": "db.local", "port": 5432, "password": "it's $ecret"},
+        "notes": "line 1\nline 2"
+    }"#;
+
+    #[test]
+    fn test_export_fields() {
+        let config = EnvConfig {
+            file_path: "config.json".to_string(),
+            fields: vec!["APP_VERSION=version".to_string(), "database".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            export_env_from_str(CONFIG, &config).unwrap(),
+            "export APP_VERSION=1.0.0\n\
+             export DATABASE_HOST=db.local\n\
+             export DATABASE_PORT=5432\n\
+             export DATABASE_PASSWORD='it'\\''s $ecret'"
+        );
+
+        let config = EnvConfig {
+            fields: vec!["notes".to_string(), "name".to_string()],
+            prefix: "INPUT_".to_string(),
+            case: NameCase::Lower,
+            style: EnvStyle::Github,
+            ..config
+        };
+        assert_eq!(
+            export_env_from_str(CONFIG, &config).unwrap(),
+            "INPUT_notes<<EOF\nline 1\nline 2\nEOF\nINPUT_name=demo"
+        );
+        // Names given as NAME= are kept as written
+        let config = EnvConfig {
+            fields: vec!["AppVersion=version".to_string(), "DB=database".to_string()],
+            style: EnvStyle::Dotenv,
+            ..config
+        };
+        assert_eq!(
+            export_env_from_str(CONFIG, &config).unwrap(),
+            "INPUT_AppVersion=1.0.0\n\
+             INPUT_DB_host=db.local\n\
+             INPUT_DB_port=5432\n\
+             INPUT_DB_password=\"it's \\$ecret\""
+        );
+    }
+
+    #[test]
+    fn test_export_sub_tree() {
+        let config = EnvConfig {
+            file_path: "config.json".to_string(),
+            field_path: Some("database".to_string()),
+            style: EnvStyle::Dotenv,
+            ..Default::default()
+        };
+        assert_eq!(
+            export_env_from_str(CONFIG, &config).unwrap(),
+            "HOST=db.local\nPORT=5432\nPASSWORD=\"it's \\$ecret\""
+        );
+
+        let config = EnvConfig {
+            field_path: Some("name".to_string()),
+            ..config
+        };
+        assert!(export_env_from_str(CONFIG, &config).is_err());
+
+        let config = EnvConfig {
+            field_path: None,
+            ..config
+        };
+        let error =
+            export_env_from_str(r#"{"a-b": 1, "x": {"a_b": 2}, "a_b": 3}"#, &config).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Variable name collision: a-b and a_b both map to A_B"
+        );
+        assert_eq!(crate::error::exit_code(&error), 7);
+    }
+}
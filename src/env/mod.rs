@@ -0,0 +1,13 @@
+//! Environment variable export functionality
+//!
+//! Turns selected fields, or a flattened sub-tree, into `KEY=value` pairs for
+//! shells, `.env` files and the GitHub Actions `$GITHUB_ENV` and
+//! `$GITHUB_OUTPUT` files.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
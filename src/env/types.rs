@@ -0,0 +1,95 @@
+//! Type definitions for env command
+
+use crate::format::Format;
+
+/// Letter case of variable names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameCase {
+    /// `DATABASE_HOST`
+    #[default]
+    Upper,
+    /// `database_host`
+    Lower,
+    /// Keys as written in the document: `database_Host`
+    Preserve,
+}
+
+impl std::str::FromStr for NameCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "upper" => Ok(Self::Upper),
+            "lower" => Ok(Self::Lower),
+            "preserve" => Ok(Self::Preserve),
+            other => Err(format!(
+                "Unknown name case: {} (expected upper, lower or preserve)",
+                other
+            )),
+        }
+    }
+}
+
+/// Syntax of the exported variables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnvStyle {
+    /// `export KEY='value'` lines for `eval`
+    #[default]
+    Shell,
+    /// `KEY="value"` lines for `.env` files
+    Dotenv,
+    /// `KEY=value` lines, with a heredoc for multi-line values, for the
+    /// GitHub Actions `$GITHUB_ENV` and `$GITHUB_OUTPUT` files
+    Github,
+}
+
+impl std::str::FromStr for EnvStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shell" => Ok(Self::Shell),
+            "dotenv" => Ok(Self::Dotenv),
+            "github" => Ok(Self::Github),
+            other => Err(format!(
+                "Unknown variable style: {} (expected shell, dotenv or github)",
+                other
+            )),
+        }
+    }
+}
+
+/// Configuration for exporting fields as environment variables
+#[derive(Debug, Clone)]
+pub struct EnvConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// Fields to export, as `PATH` or `NAME=PATH`; objects and arrays are
+    /// flattened below the field's name
+    pub fields: Vec<String>,
+    /// Sub-tree flattened when `fields` is empty (None for the whole document)
+    pub field_path: Option<String>,
+    /// Text put before every name, as given
+    pub prefix: String,
+    /// Letter case of the names derived from field paths; names given as
+    /// `NAME=` are kept as written
+    pub case: NameCase,
+    /// Syntax of the output lines
+    pub style: EnvStyle,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            format: None,
+            fields: Vec::new(),
+            field_path: None,
+            prefix: String::new(),
+            case: NameCase::Upper,
+            style: EnvStyle::Shell,
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! CLI definitions and handling for env command
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use super::core::{env_pairs, render_variables};
+use super::types::{EnvConfig, EnvStyle, NameCase};
+use crate::document;
+use crate::format::xcli::{format_arg, selected_format};
+use crate::source::read_source;
+
+/// Define the env command CLI structure
+pub fn env_command() -> Command {
+    Command::new("env")
+        .about("Export fields as KEY=value environment variables")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("Input file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(format_arg())
+        .arg(
+            Arg::new("multiple")
+                .short('m')
+                .long("multiple")
+                .value_name("[NAME=]FIELD")
+                .help("Export a field, optionally under NAME (can be used multiple times; objects and arrays are flattened)")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Flatten this sub-tree when no --multiple is given (default: the whole document)")
+                .conflicts_with("multiple"),
+        )
+        .arg(
+            Arg::new("prefix")
+                .short('p')
+                .long("prefix")
+                .value_name("PREFIX")
+                .help("Text put before every name, as given (e.g. APP_)")
+                .default_value(""),
+        )
+        .arg(
+            Arg::new("case")
+                .long("case")
+                .value_name("CASE")
+                .help("Letter case of the names derived from field paths (NAME= names are kept as written)")
+                .value_parser(["upper", "lower", "preserve"])
+                .default_value("upper"),
+        )
+        .arg(
+            Arg::new("style")
+                .short('s')
+                .long("style")
+                .value_name("STYLE")
+                .help("Output syntax: shell (export lines for eval), dotenv, or github ($GITHUB_ENV/$GITHUB_OUTPUT files)")
+                .value_parser(["shell", "dotenv", "github"])
+                .default_value("shell"),
+        )
+        .arg(
+            Arg::new("github-env")
+                .long("github-env")
+                .help("Append the variables to the file named by $GITHUB_ENV (implies --style github)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("github-output")
+                .long("github-output")
+                .help("Append the variables to the file named by $GITHUB_OUTPUT (implies --style github)")
+                .conflicts_with("github-env")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Handle env command logic
+pub fn handle_env_command(matches: &ArgMatches) -> Result<()> {
+    let github_file = if matches.get_flag("github-env") {
+        Some("GITHUB_ENV")
+    } else if matches.get_flag("github-output") {
+        Some("GITHUB_OUTPUT")
+    } else {
+        None
+    };
+
+    let config = EnvConfig {
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        format: selected_format(matches)?,
+        fields: matches
+            .get_many::<String>("multiple")
+            .map(|fields| fields.cloned().collect())
+            .unwrap_or_default(),
+        field_path: matches.get_one::<String>("field").cloned(),
+        prefix: matches.get_one::<String>("prefix").unwrap().to_string(),
+        case: matches
+            .get_one::<String>("case")
+            .unwrap()
+            .parse::<NameCase>()
+            .map_err(anyhow::Error::msg)?,
+        style: match github_file {
+            Some(_) => EnvStyle::Github,
+            None => matches
+                .get_one::<String>("style")
+                .unwrap()
+                .parse::<EnvStyle>()
+                .map_err(anyhow::Error::msg)?,
+        },
+    };
+    let content = read_source(&config.file_path)?;
+    let value = document::parse(&content, config.format, &config.file_path)?;
+    let pairs = env_pairs(&value, &config)?;
+    let output = render_variables(&pairs, config.style);
+
+    let Some(variable) = github_file else {
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+        return Ok(());
+    };
+    let path = std::env::var(variable).map_err(|_| anyhow!("${} is not set", variable))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to write to file: {}", path))?;
+    if !output.is_empty() {
+        writeln!(file, "{}", output)
+            .with_context(|| format!("Failed to write to file: {}", path))?;
+    }
+    println!("✅ Exported {} variables to ${}", pairs.len(), variable);
+    Ok(())
+}
@@ -16,6 +16,26 @@ pub enum JsonExtractError {
         error: String,
     },
 
+    /// A YAML or TOML document contains invalid syntax.
+    #[error("Invalid {format} syntax in {file}: {error}")]
+    InvalidDocument {
+        /// Name of the document format.
+        format: String,
+        /// Path to the invalid file.
+        file: String,
+        /// Description of the syntax error.
+        error: String,
+    },
+
+    /// A document uses a construct that cannot be written back without loss.
+    #[error("Unsupported {format} content: {reason}")]
+    UnsupportedDocument {
+        /// Name of the document format.
+        format: String,
+        /// The construct and why it cannot be handled.
+        reason: String,
+    },
+
     /// The specified field path does not exist in the JSON file.
     #[error("Field not found: {0}")]
     FieldNotFound(String),
@@ -58,4 +78,87 @@ pub enum JsonExtractError {
     /// The value type is invalid for the requested operation.
     #[error("Invalid value type: {0}")]
     InvalidValueType(String),
+
+    /// A regular expression or glob pattern could not be compiled.
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    /// A template contains invalid placeholder syntax or an unknown filter.
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+
+    /// A guard on a conditional update did not hold, so nothing was written.
+    #[error("Condition not met: {0}")]
+    ConditionNotMet(String),
+
+    /// Two fields map to the same variable name.
+    #[error("Variable name collision: {first} and {second} both map to {name}")]
+    NameCollision {
+        /// Path of the field that took the name first.
+        first: String,
+        /// Path of the field that maps to the same name.
+        second: String,
+        /// The shared variable name.
+        name: String,
+    },
+}
+
+impl JsonExtractError {
+    /// Process exit status used by the CLI when this error aborts a command.
+    ///
+    /// | Code | Meaning                                              |
+    /// |------|------------------------------------------------------|
+    /// | 0    | Success                                              |
+    /// | 1    | Any other error                                      |
+    /// | 2    | Invalid usage (arguments, path, glob, template)      |
+    /// | 3    | Conditional update not applied                       |
+    /// | 4    | Field, array element or container not found          |
+    /// | 5    | Invalid or unsupported document syntax               |
+    /// | 6    | File not found or I/O error                          |
+    /// | 7    | Two fields map to the same variable name             |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::InvalidFieldPath(_)
+            | Self::InvalidArrayIndex(_)
+            | Self::InvalidPattern(_)
+            | Self::InvalidTemplate(_) => 2,
+            Self::ConditionNotMet(_) => 3,
+            Self::FieldNotFound(_)
+            | Self::ArrayIndexOutOfBounds { .. }
+            | Self::NotAnArray(_)
+            | Self::NotAnObject(_) => 4,
+            Self::InvalidJson { .. }
+            | Self::InvalidDocument { .. }
+            | Self::UnsupportedDocument { .. }
+            | Self::JsonError(_) => 5,
+            Self::FileNotFound(_) | Self::IoError(_) => 6,
+            Self::NameCollision { .. } => 7,
+            Self::InvalidValueType(_) => 1,
+        }
+    }
+}
+
+/// Failure a command has already reported (or silenced with `--quiet`)
+///
+/// The CLI exits with the carried status without printing another message.
+#[derive(Error, Debug)]
+#[error("command failed with exit status {0}")]
+pub struct ExitStatus(pub i32);
+
+/// Exit status for an error chain, decided by its first library or I/O error
+///
+/// See [`JsonExtractError::exit_code`] for the meaning of each status.
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(status) = cause.downcast_ref::<ExitStatus>() {
+                Some(status.0)
+            } else if let Some(error) = cause.downcast_ref::<JsonExtractError>() {
+                Some(error.exit_code())
+            } else {
+                cause.is::<std::io::Error>().then_some(6)
+            }
+        })
+        .unwrap_or(1)
 }
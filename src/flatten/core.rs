@@ -0,0 +1,291 @@
+//! Core functionality for flatten and unflatten commands
+
+use anyhow::Result;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashSet;
+
+use super::types::{ArrayNotation, FlattenConfig};
+use crate::convert::core::convert_value;
+use crate::document;
+use crate::error::JsonExtractError;
+use crate::get::utils::value_type_name;
+use crate::path::{format_path, parse_path, Segment};
+use crate::source::read_source;
+
+/// Flatten a file (`-` reads stdin) and return the result in the target syntax
+pub fn flatten(config: &FlattenConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    flatten_str(&content, config)
+}
+
+/// Flatten document text and return the result in the target syntax
+pub fn flatten_str(content: &str, config: &FlattenConfig) -> Result<String> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let flat = flatten_value(
+        document::select(&value, config.field_path.as_deref())?,
+        &config.separator,
+        config.arrays,
+    );
+    convert_value(&JsonValue::Object(flat), config.target)
+}
+
+/// Unflatten a file (`-` reads stdin) and return the result in the target syntax
+pub fn unflatten(config: &FlattenConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    unflatten_str(&content, config)
+}
+
+/// Unflatten document text and return the result in the target syntax
+pub fn unflatten_str(content: &str, config: &FlattenConfig) -> Result<String> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let flat = document::select(&value, config.field_path.as_deref())?;
+    let map = flat.as_object().ok_or_else(|| {
+        JsonExtractError::InvalidValueType(format!(
+            "only an object can be unflattened, not {}",
+            value_type_name(flat)
+        ))
+    })?;
+    let nested = unflatten_value(map, &config.separator, config.arrays)?;
+    convert_value(&nested, config.target)
+}
+
+/// Map every leaf of `value` to its key, in document order
+///
+/// Scalars and empty objects and arrays below the root are leaves; a scalar
+/// document becomes a single entry with an empty key.
+pub fn flatten_value(
+    value: &JsonValue,
+    separator: &str,
+    arrays: ArrayNotation,
+) -> Map<String, JsonValue> {
+    fn visit(
+        value: &JsonValue,
+        segments: &mut Vec<Segment>,
+        key: &dyn Fn(&[Segment]) -> String,
+        out: &mut Map<String, JsonValue>,
+    ) {
+        let children: Vec<(Segment, &JsonValue)> = match value {
+            JsonValue::Object(map) => map
+                .iter()
+                .map(|(k, child)| (Segment::Key(k.clone()), child))
+                .collect(),
+            JsonValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, child)| (Segment::Index(i), child))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if children.is_empty() {
+            if !segments.is_empty() || !(value.is_object() || value.is_array()) {
+                out.insert(key(segments), value.clone());
+            }
+            return;
+        }
+        for (segment, child) in children {
+            segments.push(segment);
+            visit(child, segments, key, out);
+            segments.pop();
+        }
+    }
+
+    let key = |segments: &[Segment]| join_key(segments, separator, arrays);
+    let mut out = Map::new();
+    visit(value, &mut Vec::new(), &key, &mut out);
+    out
+}
+
+/// Rebuild the nested document described by flattened keys
+///
+/// Keys are split as [`flatten_value`] writes them; array indexes may come in
+/// any order, and gaps are filled with null. Fails when one key is a prefix
+/// of another, e.g. `a` and `a.b`.
+pub fn unflatten_value(
+    map: &Map<String, JsonValue>,
+    separator: &str,
+    arrays: ArrayNotation,
+) -> Result<JsonValue, JsonExtractError> {
+    let mut root = JsonValue::Null;
+    // Paths of the values set so far, and of the containers above them
+    let mut leaves = HashSet::new();
+    let mut parents = HashSet::new();
+
+    for (key, value) in map {
+        let conflict =
+            || JsonExtractError::InvalidFieldPath(format!("{} conflicts with another key", key));
+        let segments = split_key(key, separator, arrays)?;
+        if parents.contains(&segments[..])
+            || (0..=segments.len()).any(|end| leaves.contains(&segments[..end]))
+        {
+            return Err(conflict());
+        }
+
+        let mut current = &mut root;
+        for (i, segment) in segments.iter().enumerate() {
+            parents.insert(segments[..i].to_vec());
+            // Nulls are placeholders for containers not created yet
+            if current.is_null() {
+                *current = match segment {
+                    Segment::Key(_) => JsonValue::Object(Map::new()),
+                    Segment::Index(_) => JsonValue::Array(Vec::new()),
+                };
+            }
+            current = match (segment, current) {
+                (Segment::Key(k), JsonValue::Object(object)) => {
+                    object.entry(k.clone()).or_insert(JsonValue::Null)
+                }
+                (Segment::Index(index), JsonValue::Array(items)) => {
+                    if items.len() <= *index {
+                        items.resize(index + 1, JsonValue::Null);
+                    }
+                    &mut items[*index]
+                }
+                _ => return Err(conflict()),
+            };
+        }
+        *current = value.clone();
+        leaves.insert(segments);
+    }
+
+    if map.is_empty() {
+        root = JsonValue::Object(Map::new());
+    }
+    Ok(root)
+}
+
+/// Key of a leaf from its path segments
+fn join_key(segments: &[Segment], separator: &str, arrays: ArrayNotation) -> String {
+    if separator == "." && arrays == ArrayNotation::Bracket {
+        return format_path(segments);
+    }
+    let mut key = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match (segment, arrays) {
+            (Segment::Index(index), ArrayNotation::Bracket) => {
+                key.push_str(&format!("[{}]", index));
+                continue;
+            }
+            _ if i > 0 => key.push_str(separator),
+            _ => {}
+        }
+        match segment {
+            Segment::Key(k) => key.push_str(k),
+            Segment::Index(index) => key.push_str(&index.to_string()),
+        }
+    }
+    key
+}
+
+/// Path segments of a flattened key, the reverse of [`join_key`]
+///
+/// The empty key stands for the document root.
+fn split_key(
+    key: &str,
+    separator: &str,
+    arrays: ArrayNotation,
+) -> Result<Vec<Segment>, JsonExtractError> {
+    if key.is_empty() {
+        return Ok(Vec::new());
+    }
+    if separator == "." && arrays == ArrayNotation::Bracket {
+        return parse_path(key);
+    }
+
+    let mut segments = Vec::new();
+    for part in key.split(separator) {
+        match arrays {
+            ArrayNotation::Index
+                if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                segments.push(Segment::Index(part.parse().map_err(|_| {
+                    JsonExtractError::InvalidArrayIndex(format!("Invalid array index: {}", part))
+                })?));
+            }
+            ArrayNotation::Index => segments.push(Segment::Key(part.to_string())),
+            ArrayNotation::Bracket => {
+                // Trailing `[n]` groups are indexes of the key before them
+                let mut name = part;
+                let mut indexes = Vec::new();
+                while let Some(open) = name.strip_suffix(']').and_then(|rest| rest.rfind('[')) {
+                    match name[open + 1..name.len() - 1].parse::<usize>() {
+                        Ok(index) => indexes.push(Segment::Index(index)),
+                        Err(_) => break,
+                    }
+                    name = &name[..open];
+                }
+                if !name.is_empty() || indexes.is_empty() {
+                    segments.push(Segment::Key(name.to_string()));
+                }
+                segments.extend(indexes.into_iter().rev());
+            }
+        }
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_round_trip() {
+        let value = json!({
+            "app": {"title": "Demo", "menu.items": ["Open", {"label": "Quit"}]},
+            "empty": {},
+        });
+        let flat = flatten_value(&value, ".", ArrayNotation::Bracket);
+
+        assert_eq!(
+            JsonValue::Object(flat.clone()),
+            json!({
+                "app.title": "Demo",
+                "app[\"menu.items\"][0]": "Open",
+                "app[\"menu.items\"][1].label": "Quit",
+                "empty": {},
+            })
+        );
+        assert_eq!(
+            unflatten_value(&flat, ".", ArrayNotation::Bracket).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_flatten_separator_and_index_notation() {
+        let value = json!({"db": {"hosts": ["a", "b"], "port": 5432}});
+        let flat = flatten_value(&value, "__", ArrayNotation::Index);
+        assert_eq!(
+            flat.keys().collect::<Vec<_>>(),
+            ["db__hosts__0", "db__hosts__1", "db__port"]
+        );
+        assert_eq!(
+            unflatten_value(&flat, "__", ArrayNotation::Index).unwrap(),
+            value
+        );
+
+        let flat = flatten_value(&value, "/", ArrayNotation::Bracket);
+        assert!(flat.contains_key("db/hosts[1]"));
+        assert_eq!(
+            unflatten_value(&flat, "/", ArrayNotation::Bracket).unwrap(),
+            value
+        );
+
+        let sparse = json!({"list[2]": "c", "list[0]": "a"});
+        assert_eq!(
+            unflatten_value(sparse.as_object().unwrap(), ".", ArrayNotation::Bracket).unwrap(),
+            json!({"list": ["a", null, "c"]})
+        );
+        let conflicting = json!({"a.b": 1, "a": 2});
+        assert_eq!(
+            unflatten_value(
+                conflicting.as_object().unwrap(),
+                ".",
+                ArrayNotation::Bracket
+            )
+            .unwrap_err()
+            .to_string(),
+            "Invalid field path: a conflicts with another key"
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Flattening functionality
+//!
+//! Turns nested documents into a single object of `"a.b[0].c": value` pairs
+//! and back, e.g. for i18n catalogs and environment overlays.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
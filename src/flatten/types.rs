@@ -0,0 +1,63 @@
+//! Type definitions for flatten and unflatten commands
+
+use crate::convert::types::Target;
+use crate::format::Format;
+
+/// How array elements appear in flattened keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayNotation {
+    /// Index in brackets: `files[0]`
+    #[default]
+    Bracket,
+    /// Index as a key segment: `files.0` (digit-only segments unflatten to arrays)
+    Index,
+}
+
+impl std::str::FromStr for ArrayNotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bracket" => Ok(Self::Bracket),
+            "index" => Ok(Self::Index),
+            other => Err(format!(
+                "Unknown array notation: {} (expected bracket or index)",
+                other
+            )),
+        }
+    }
+}
+
+/// Configuration for flattening and unflattening
+#[derive(Debug, Clone)]
+pub struct FlattenConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// Dot-separated path of the sub-tree to process (None for the whole document)
+    pub field_path: Option<String>,
+    /// Text between the segments of a flattened key
+    ///
+    /// With the default `.` and bracket notation, keys use the field path
+    /// syntax, quoting keys that contain `.`, `[` or `]`; other separators
+    /// join segments as they are.
+    pub separator: String,
+    /// How array elements appear in flattened keys
+    pub arrays: ArrayNotation,
+    /// Output syntax
+    pub target: Target,
+}
+
+impl Default for FlattenConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            format: None,
+            field_path: None,
+            separator: ".".to_string(),
+            arrays: ArrayNotation::Bracket,
+            target: Target::Json,
+        }
+    }
+}
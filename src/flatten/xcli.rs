@@ -0,0 +1,125 @@
+//! CLI definitions and handling for flatten and unflatten commands
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use super::core::{flatten, unflatten};
+use super::types::{ArrayNotation, FlattenConfig};
+use crate::convert::types::Target;
+use crate::format::xcli::{format_arg, selected_format};
+use crate::source::{write_output, STDIO_PATH};
+
+/// Define the flatten command CLI structure
+pub fn flatten_command() -> Command {
+    common_args(Command::new("flatten"))
+        .about("Flatten a document into a single object of \"a.b[0].c\": value pairs")
+}
+
+/// Define the unflatten command CLI structure
+pub fn unflatten_command() -> Command {
+    common_args(Command::new("unflatten"))
+        .about("Rebuild a nested document from an object of flattened keys")
+}
+
+/// Add the arguments shared by the flatten and unflatten commands
+fn common_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("Input file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Process only the value at this dot-separated path"),
+        )
+        .arg(format_arg())
+        .arg(
+            Arg::new("separator")
+                .short('s')
+                .long("separator")
+                .value_name("TEXT")
+                .help("Text between key segments (with '.' and bracket arrays, awkward keys are quoted as in field paths)")
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("arrays")
+                .short('a')
+                .long("arrays")
+                .value_name("NOTATION")
+                .help("Array notation in keys: bracket (files[0]) or index (files.0)")
+                .value_parser(["bracket", "index"])
+                .default_value("bracket"),
+        )
+        .arg(
+            Arg::new("to")
+                .short('t')
+                .long("to")
+                .value_name("FORMAT")
+                .help("Output format (json is pretty-printed, json-min is a single line)")
+                .value_parser(["json", "json-min", "jsonc", "json5", "yaml", "yml", "toml"])
+                .default_value("json"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Output file path ('-' for stdout)")
+                .default_value(STDIO_PATH),
+        )
+}
+
+/// Handle flatten command logic
+pub fn handle_flatten_command(matches: &ArgMatches) -> Result<()> {
+    let config = config(matches)?;
+    write_result(matches, &flatten(&config)?, "Flattened", &config.file_path)
+}
+
+/// Handle unflatten command logic
+pub fn handle_unflatten_command(matches: &ArgMatches) -> Result<()> {
+    let config = config(matches)?;
+    write_result(
+        matches,
+        &unflatten(&config)?,
+        "Unflattened",
+        &config.file_path,
+    )
+}
+
+fn config(matches: &ArgMatches) -> Result<FlattenConfig> {
+    Ok(FlattenConfig {
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        format: selected_format(matches)?,
+        field_path: matches.get_one::<String>("field").cloned(),
+        separator: matches.get_one::<String>("separator").unwrap().to_string(),
+        arrays: matches
+            .get_one::<String>("arrays")
+            .unwrap()
+            .parse::<ArrayNotation>()
+            .map_err(anyhow::Error::msg)?,
+        target: matches
+            .get_one::<String>("to")
+            .unwrap()
+            .parse::<Target>()
+            .map_err(anyhow::Error::msg)?,
+    })
+}
+
+/// Print the result, or write it to the output file and report it
+fn write_result(matches: &ArgMatches, output: &str, action: &str, input: &str) -> Result<()> {
+    let path = matches.get_one::<String>("output").unwrap();
+    if path == STDIO_PATH {
+        write_output(path, output)?;
+    } else {
+        write_output(path, &format!("{}\n", output))?;
+        println!("✅ {} {} into {}", action, input, path);
+    }
+    Ok(())
+}
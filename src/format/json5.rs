@@ -0,0 +1,129 @@
+//! JSON5
+//!
+//! Extends JSONC with unquoted keys, single-quoted strings, hex numbers,
+//! leading or trailing decimal points, `Infinity` and `NaN`. Edits keep the
+//! original text of untouched values; inserted keys and strings follow the
+//! quoting style used by the rest of the document.
+
+use serde_json::Value as JsonValue;
+
+use super::jsonc::{emit_dialect, non_finite_token, parse_dialect, write_dialect, Dialect};
+use crate::error::JsonExtractError;
+
+/// Parse JSON5 text into a JSON value
+///
+/// `Infinity`, `-Infinity` and `NaN` have no JSON equivalent and are read as
+/// placeholders; they are written back unchanged as long as they are not
+/// edited, and [`check_finite`] rejects them where a JSON value is needed.
+pub fn parse(content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+    parse_dialect(content, source, Dialect::Json5)
+}
+
+/// Serialize `value` by editing `original`, preserving comments and JSON5 style
+pub fn write(original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+    write_dialect(original, value, Dialect::Json5)
+}
+
+/// Fail when `value` holds a JSON5 `Infinity` or `NaN`, which JSON cannot represent
+pub fn check_finite(value: &JsonValue) -> Result<(), JsonExtractError> {
+    let token = match value {
+        JsonValue::String(text) => non_finite_token(text),
+        JsonValue::Array(items) => return items.iter().try_for_each(check_finite),
+        JsonValue::Object(map) => return map.values().try_for_each(check_finite),
+        _ => None,
+    };
+    match token {
+        Some(token) => Err(JsonExtractError::UnsupportedDocument {
+            format: "JSON5".to_string(),
+            reason: format!("{} cannot be represented in JSON", token),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// JSON5 backend
+pub struct Json5Backend;
+
+impl super::Backend for Json5Backend {
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse(content, source)
+    }
+
+    fn write(&self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        write(original, value)
+    }
+
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        Ok(emit_dialect(value, Dialect::Json5))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const RENOVATE: &str = r#"{
+  // Renovate configuration
+  extends: ['config:base'],
+  prHourlyLimit: 0x0A,
+  ratio: .5,
+  max: +Infinity,
+  'label-prefix': 'deps: \'auto\'',
+}
+"#;
+
+    #[test]
+    fn test_parse_json5() {
+        assert_eq!(
+            parse(RENOVATE, "renovate.json5").unwrap(),
+            json!({
+                "extends": ["config:base"],
+                "prHourlyLimit": 10,
+                "ratio": 0.5,
+                "max": format!("{}+Infinity", "\u{0}json5:"),
+                "label-prefix": "deps: 'auto'",
+            })
+        );
+        assert!(parse("{a: 01x}", "x.json5").is_err());
+        assert!(parse("{'a: 1}", "x.json5").is_err());
+    }
+
+    #[test]
+    fn test_check_finite() {
+        let value = parse("{a: 1, b: [NaN], c: {d: -Infinity}}", "x.json5").unwrap();
+        assert!(check_finite(&value["a"]).is_ok());
+        let error = check_finite(&value["c"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unsupported JSON5 content: -Infinity cannot be represented in JSON"
+        );
+        assert_eq!(error.exit_code(), 5);
+        assert!(check_finite(&value["b"]).is_err());
+        assert!(check_finite(&value).is_err());
+    }
+
+    #[test]
+    fn test_write_keeps_json5_style() {
+        let mut value = parse(RENOVATE, "renovate.json5").unwrap();
+        value["prHourlyLimit"] = json!(2);
+        value["schedule"] = json!(["before 5am"]);
+        value["extends"][0] = json!("config:recommended");
+
+        assert_eq!(
+            write(RENOVATE, &value).unwrap(),
+            r#"{
+  // Renovate configuration
+  extends: ['config:recommended'],
+  prHourlyLimit: 2,
+  ratio: .5,
+  max: +Infinity,
+  'label-prefix': 'deps: \'auto\'',
+  schedule: [
+    'before 5am'
+  ],
+}
+"#
+        );
+    }
+}
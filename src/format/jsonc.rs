@@ -0,0 +1,945 @@
+//! JSON with comments (JSONC)
+//!
+//! Accepts `//` and `/* */` comments and trailing commas, as used by
+//! tsconfig.json and VS Code settings. Edits are written back by re-rendering
+//! only the parts of the document that changed, so comments, whitespace and
+//! key order of untouched members are kept verbatim. The same parser backs
+//! the JSON5 format.
+
+use serde_json::{Map, Value as JsonValue};
+use std::ops::Range;
+
+use crate::error::JsonExtractError;
+
+/// Parse JSONC text into a JSON value
+pub fn parse(content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+    parse_dialect(content, source, Dialect::Jsonc)
+}
+
+/// Serialize `value` by editing `original`, preserving comments and formatting
+pub fn write(original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+    write_dialect(original, value, Dialect::Jsonc)
+}
+
+/// JSONC backend
+pub struct JsoncBackend;
+
+impl super::Backend for JsoncBackend {
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse(content, source)
+    }
+
+    fn write(&self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        write(original, value)
+    }
+
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        Ok(emit_dialect(value, Dialect::Jsonc))
+    }
+}
+
+/// Relaxed JSON syntax accepted by the lossless parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Dialect {
+    /// Comments and trailing commas
+    Jsonc,
+    /// JSONC plus unquoted keys, single quotes, hex numbers, Infinity and NaN
+    Json5,
+}
+
+/// Prefix of the strings that stand in for JSON5 `Infinity` and `NaN`
+///
+/// They are read as this prefix followed by their original token, so that an
+/// untouched value is written back as it was. [`non_finite_token`] tells them
+/// apart from real strings.
+const NON_FINITE: &str = "\u{0}json5:";
+
+/// The original `Infinity` or `NaN` token a parsed JSON5 string stands for
+pub(super) fn non_finite_token(text: &str) -> Option<&str> {
+    text.strip_prefix(NON_FINITE)
+}
+
+/// Parse text of the given dialect into a JSON value
+pub(super) fn parse_dialect(
+    content: &str,
+    source: &str,
+    dialect: Dialect,
+) -> Result<JsonValue, JsonExtractError> {
+    Ok(parse_tree(content, source, dialect)?.0.to_value())
+}
+
+/// Serialize `value` by editing `original` written in the given dialect
+pub(super) fn write_dialect(
+    original: &str,
+    value: &JsonValue,
+    dialect: Dialect,
+) -> Result<String, JsonExtractError> {
+    let (root, quoting) = parse_tree(original, "<original>", dialect)?;
+    let style = Style::detect(original, quoting, dialect);
+    let span = root.span();
+
+    let mut output = String::with_capacity(original.len());
+    output.push_str(&original[..span.start]);
+    output.push_str(&render(original, &root, value, &style));
+    output.push_str(&original[span.end..]);
+    Ok(output)
+}
+
+/// Serialize `value` as a new document: two-space indentation, and for JSON5
+/// bare keys and single-quoted strings
+pub(super) fn emit_dialect(value: &JsonValue, dialect: Dialect) -> String {
+    let json5 = dialect == Dialect::Json5;
+    let style = Style {
+        indent: "  ".to_string(),
+        compact: false,
+        dialect,
+        quoting: Quoting {
+            bare_keys: json5,
+            quote: if json5 { '\'' } else { '"' },
+        },
+    };
+    style.fresh(value, "")
+}
+
+/// Syntax tree keeping the byte ranges of every token and the trivia around them
+#[derive(Debug)]
+enum Node {
+    Object(Container<Member>),
+    Array(Container<Item>),
+    Scalar {
+        span: Range<usize>,
+        value: JsonValue,
+    },
+}
+
+/// Object or array with its entries and surrounding trivia
+#[derive(Debug)]
+struct Container<T> {
+    span: Range<usize>,
+    entries: Vec<T>,
+    /// Whether the last entry is followed by a comma
+    trailing_comma: bool,
+    /// Trivia between the last entry and the closing bracket
+    tail: Range<usize>,
+}
+
+/// Object member: `leading "key" colon value before_comma , trailing`
+#[derive(Debug)]
+struct Member {
+    key: String,
+    leading: Range<usize>,
+    colon: Range<usize>,
+    item: Item,
+}
+
+/// Array item (also the value part of an object member)
+#[derive(Debug)]
+struct Item {
+    leading: Range<usize>,
+    value: Node,
+    before_comma: Range<usize>,
+    /// Comments on the same line after the comma (or after the value)
+    trailing: Range<usize>,
+}
+
+impl Node {
+    fn span(&self) -> Range<usize> {
+        match self {
+            Node::Object(c) => c.span.clone(),
+            Node::Array(c) => c.span.clone(),
+            Node::Scalar { span, .. } => span.clone(),
+        }
+    }
+
+    fn to_value(&self) -> JsonValue {
+        match self {
+            Node::Object(c) => JsonValue::Object(
+                c.entries
+                    .iter()
+                    .map(|m| (m.key.clone(), m.item.value.to_value()))
+                    .collect::<Map<_, _>>(),
+            ),
+            Node::Array(c) => {
+                JsonValue::Array(c.entries.iter().map(|i| i.value.to_value()).collect())
+            }
+            Node::Scalar { value, .. } => value.clone(),
+        }
+    }
+
+    /// Whether the node already represents `value` (including key order)
+    fn matches(&self, value: &JsonValue) -> bool {
+        match (self, value) {
+            (Node::Object(c), JsonValue::Object(map)) => {
+                c.entries.len() == map.len()
+                    && c.entries
+                        .iter()
+                        .zip(map)
+                        .all(|(m, (key, value))| m.key == *key && m.item.value.matches(value))
+            }
+            (Node::Array(c), JsonValue::Array(items)) => {
+                c.entries.len() == items.len()
+                    && c.entries
+                        .iter()
+                        .zip(items)
+                        .all(|(item, value)| item.value.matches(value))
+            }
+            (Node::Scalar { value: own, .. }, value) => own == value,
+            _ => false,
+        }
+    }
+}
+
+/// Quoting conventions seen while parsing
+#[derive(Debug, Clone, Copy)]
+struct Quoting {
+    /// Whether object keys are written as bare identifiers where possible
+    bare_keys: bool,
+    /// Quote character used for strings
+    quote: char,
+}
+
+/// Layout conventions detected from the original document
+struct Style {
+    /// Indentation unit (e.g., two spaces or a tab)
+    indent: String,
+    /// Whether the document is written on a single line
+    compact: bool,
+    dialect: Dialect,
+    quoting: Quoting,
+}
+
+impl Style {
+    fn detect(original: &str, quoting: Quoting, dialect: Dialect) -> Self {
+        let body = original.trim();
+        let indent = body
+            .lines()
+            .skip(1)
+            .find(|line| line.starts_with([' ', '\t']))
+            .map(|line| {
+                if line.starts_with('\t') {
+                    "\t".to_string()
+                } else {
+                    line.chars().take_while(|c| *c == ' ').collect()
+                }
+            })
+            .unwrap_or_else(|| "  ".to_string());
+        Self {
+            indent,
+            compact: !body.contains('\n'),
+            dialect,
+            quoting,
+        }
+    }
+
+    /// Serialize a new value starting on a line indented by `base`
+    fn fresh(&self, value: &JsonValue, base: &str) -> String {
+        let mut output = String::new();
+        self.serialize(&mut output, value, base, self.compact);
+        output
+    }
+
+    /// Serialize a new value on a single line
+    fn inline(&self, value: &JsonValue) -> String {
+        let mut output = String::new();
+        self.serialize(&mut output, value, "", true);
+        output
+    }
+
+    fn serialize(&self, output: &mut String, value: &JsonValue, base: &str, compact: bool) {
+        let entries: Vec<(Option<&String>, &JsonValue)> = match value {
+            JsonValue::Object(map) => map.iter().map(|(k, v)| (Some(k), v)).collect(),
+            JsonValue::Array(items) => items.iter().map(|v| (None, v)).collect(),
+            JsonValue::String(text) => {
+                return match non_finite_token(text).filter(|_| self.dialect == Dialect::Json5) {
+                    Some(token) => output.push_str(token),
+                    None => output.push_str(&self.string(text)),
+                }
+            }
+            other => return output.push_str(&other.to_string()),
+        };
+        let (open, close) = if value.is_object() {
+            ('{', '}')
+        } else {
+            ('[', ']')
+        };
+        output.push(open);
+        if entries.is_empty() {
+            output.push(close);
+            return;
+        }
+
+        let inner = format!("{}{}", base, self.indent);
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+            if !compact {
+                output.push('\n');
+                output.push_str(&inner);
+            }
+            if let Some(key) = key {
+                output.push_str(&self.key(key));
+                output.push_str(if compact { ":" } else { ": " });
+            }
+            self.serialize(output, value, &inner, compact);
+        }
+        if !compact {
+            output.push('\n');
+            output.push_str(base);
+        }
+        output.push(close);
+    }
+
+    /// Write an object key, bare when the document uses bare keys
+    fn key(&self, key: &str) -> String {
+        if self.quoting.bare_keys && is_identifier(key) {
+            key.to_string()
+        } else {
+            self.string(key)
+        }
+    }
+
+    /// Quote and escape a string with the document's quote character
+    fn string(&self, text: &str) -> String {
+        let quote = self.quoting.quote;
+        let mut output = String::with_capacity(text.len() + 2);
+        output.push(quote);
+        for c in text.chars() {
+            match c {
+                '\\' => output.push_str("\\\\"),
+                '\n' => output.push_str("\\n"),
+                '\r' => output.push_str("\\r"),
+                '\t' => output.push_str("\\t"),
+                c if c == quote => {
+                    output.push('\\');
+                    output.push(c);
+                }
+                c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+                c => output.push(c),
+            }
+        }
+        output.push(quote);
+        output
+    }
+}
+
+/// Whether `key` can be written as a bare JSON5 identifier
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Leading whitespace of the line containing byte offset `pos`
+fn line_indent(src: &str, pos: usize) -> &str {
+    let line_start = src[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &src[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Render the text for `node` so that it represents `value`
+fn render(src: &str, node: &Node, value: &JsonValue, style: &Style) -> String {
+    if node.matches(value) {
+        return src[node.span()].to_string();
+    }
+
+    let base = line_indent(src, node.span().start);
+    match (node, value) {
+        (Node::Object(c), JsonValue::Object(map)) => {
+            let colon = c
+                .entries
+                .first()
+                .map_or(if style.compact { ":" } else { ": " }, |m| {
+                    &src[m.colon.clone()]
+                });
+            let entries = map
+                .iter()
+                .map(
+                    |(key, value)| match c.entries.iter().find(|m| m.key == *key) {
+                        Some(m) => {
+                            let prefix = &src[m.leading.start..m.colon.end];
+                            Entry::kept(src, prefix, &m.item, value, style)
+                        }
+                        None => Entry::Inserted {
+                            prefix: format!("{}{}", style.key(key), colon),
+                            value,
+                        },
+                    },
+                )
+                .collect();
+            render_container(src, c, entries, base, style, ('{', '}'), |m| &m.leading)
+        }
+        (Node::Array(c), JsonValue::Array(items)) => {
+            let entries = items
+                .iter()
+                .enumerate()
+                .map(|(i, value)| match c.entries.get(i) {
+                    Some(item) => Entry::kept(src, &src[item.leading.clone()], item, value, style),
+                    None => Entry::Inserted {
+                        prefix: String::new(),
+                        value,
+                    },
+                })
+                .collect();
+            render_container(src, c, entries, base, style, ('[', ']'), |i| &i.leading)
+        }
+        _ => style.fresh(value, base),
+    }
+}
+
+/// Entry of a re-rendered container, before separators are added
+enum Entry<'a> {
+    /// Entry present in the original document
+    Kept {
+        /// Leading trivia, key and value text (without comma)
+        body: String,
+        /// Same-line trivia following the comma
+        trailing: String,
+    },
+    /// Entry that did not exist before
+    Inserted {
+        /// Quoted key and colon for objects, empty for arrays
+        prefix: String,
+        /// Value to serialize
+        value: &'a JsonValue,
+    },
+}
+
+impl<'a> Entry<'a> {
+    fn kept(src: &str, prefix: &str, item: &Item, value: &JsonValue, style: &Style) -> Self {
+        Entry::Kept {
+            body: format!(
+                "{}{}{}",
+                prefix,
+                render(src, &item.value, value, style),
+                &src[item.before_comma.clone()]
+            ),
+            trailing: src[item.trailing.clone()].to_string(),
+        }
+    }
+}
+
+/// Join rendered entries with commas and the trivia of the original container
+fn render_container<T>(
+    src: &str,
+    container: &Container<T>,
+    entries: Vec<Entry>,
+    base: &str,
+    style: &Style,
+    (open, close): (char, char),
+    leading: impl Fn(&T) -> &Range<usize>,
+) -> String {
+    // Inserted entries reuse the line break and indentation of the last entry
+    let new_leading = match container.entries.last() {
+        Some(last) => {
+            let text = &src[leading(last).clone()];
+            match text.rfind('\n') {
+                Some(i) => format!("\n{}", &text[i + 1..]),
+                None if text.is_empty() && !style.compact => " ".to_string(),
+                None => text.to_string(),
+            }
+        }
+        None if style.compact => String::new(),
+        None => format!("\n{}{}", base, style.indent),
+    };
+    let new_indent = new_leading.rsplit('\n').next().unwrap_or_default();
+    let tail = if container.entries.is_empty() && !entries.is_empty() && !style.compact {
+        format!("\n{}", base)
+    } else {
+        src[container.tail.clone()].to_string()
+    };
+
+    let count = entries.len();
+    let mut output = String::new();
+    output.push(open);
+    for (i, entry) in entries.into_iter().enumerate() {
+        let comma = if i + 1 < count || container.trailing_comma {
+            ","
+        } else {
+            ""
+        };
+        match entry {
+            Entry::Kept { body, trailing } => {
+                output.push_str(&body);
+                output.push_str(comma);
+                output.push_str(&trailing);
+            }
+            Entry::Inserted { prefix, value } => {
+                output.push_str(&new_leading);
+                output.push_str(&prefix);
+                if new_leading.contains('\n') {
+                    output.push_str(&style.fresh(value, new_indent));
+                } else {
+                    // Entries of single-line containers stay on that line
+                    output.push_str(&style.inline(value));
+                }
+                output.push_str(comma);
+            }
+        }
+    }
+    output.push_str(&tail);
+    output.push(close);
+    output
+}
+
+/// Parse text into a syntax tree, requiring a single value surrounded by trivia
+fn parse_tree(
+    content: &str,
+    source: &str,
+    dialect: Dialect,
+) -> Result<(Node, Quoting), JsonExtractError> {
+    let mut parser = Parser {
+        src: content,
+        pos: 0,
+        source,
+        dialect,
+        bare_keys: 0,
+        quoted_keys: 0,
+        single_quoted: 0,
+        double_quoted: 0,
+    };
+    parser.skip_trivia()?;
+    let root = parser.parse_value()?;
+    parser.skip_trivia()?;
+    if parser.pos < content.len() {
+        return Err(parser.error("Trailing characters after JSON value"));
+    }
+
+    let quoting = Quoting {
+        bare_keys: parser.bare_keys > parser.quoted_keys,
+        quote: if parser.single_quoted > parser.double_quoted {
+            '\''
+        } else {
+            '"'
+        },
+    };
+    Ok((root, quoting))
+}
+
+/// Recursive-descent parser over the source text
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    source: &'a str,
+    dialect: Dialect,
+    /// Number of bare and quoted keys, and of single- and double-quoted strings
+    bare_keys: usize,
+    quoted_keys: usize,
+    single_quoted: usize,
+    double_quoted: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> JsonExtractError {
+        let consumed = &self.src[..self.pos.min(self.src.len())];
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed.len() - consumed.rfind('\n').map_or(0, |i| i + 1) + 1;
+        JsonExtractError::InvalidJson {
+            file: self.source.to_string(),
+            error: format!("{} at line {} column {}", message, line, column),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonExtractError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", byte as char)))
+        }
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), JsonExtractError> {
+        self.skip_trivia_until_newline(true)
+    }
+
+    /// Skip whitespace and comments on the current line, returning the range
+    fn same_line_trivia(&mut self) -> Result<Range<usize>, JsonExtractError> {
+        let start = self.pos;
+        self.skip_trivia_until_newline(false)?;
+        Ok(start..self.pos)
+    }
+
+    fn skip_trivia_until_newline(&mut self, cross_lines: bool) -> Result<(), JsonExtractError> {
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'\n' | b'\r' if !cross_lines => break,
+                b' ' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                // Vertical tab, form feed, NBSP and BOM are whitespace in JSON5
+                0x0b | 0x0c if self.dialect == Dialect::Json5 => self.pos += 1,
+                0xc2 | 0xef
+                    if self.dialect == Dialect::Json5
+                        && (self.src[self.pos..].starts_with('\u{a0}')
+                            || self.src[self.pos..].starts_with('\u{feff}')) =>
+                {
+                    self.pos += self.src[self.pos..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8);
+                }
+                b'/' if self.src[self.pos..].starts_with("//") => {
+                    self.pos = self.src[self.pos..]
+                        .find('\n')
+                        .map_or(self.src.len(), |i| self.pos + i);
+                    // A line comment ends the same-line trivia before its newline
+                    if self.src[..self.pos].ends_with('\r') {
+                        self.pos -= 1;
+                    }
+                }
+                b'/' if self.src[self.pos..].starts_with("/*") => {
+                    let end = self.src[self.pos + 2..]
+                        .find("*/")
+                        .ok_or_else(|| self.error("Unterminated block comment"))?;
+                    self.pos += end + 4;
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Node, JsonExtractError> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') | Some(b'\'') => {
+                let start = self.pos;
+                let value = JsonValue::String(self.parse_string()?);
+                Ok(Node::Scalar {
+                    span: start..self.pos,
+                    value,
+                })
+            }
+            Some(_) => self.parse_literal(),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonExtractError> {
+        let start = self.pos;
+        let quote = match self.peek() {
+            Some(b'\'') if self.dialect == Dialect::Json5 => b'\'',
+            _ => b'"',
+        };
+        self.expect(quote)?;
+        let bytes = self.src.as_bytes();
+        while self.pos < bytes.len() && bytes[self.pos] != quote {
+            self.pos += if bytes[self.pos] == b'\\' { 2 } else { 1 };
+        }
+        if self.pos >= bytes.len() {
+            return Err(self.error("Unterminated string"));
+        }
+        self.pos += 1;
+
+        let token = &self.src[start..self.pos];
+        if quote == b'\'' {
+            self.single_quoted += 1;
+        } else {
+            self.double_quoted += 1;
+        }
+        match self.dialect {
+            Dialect::Jsonc => serde_json::from_str(token).map_err(|e| self.error(&e.to_string())),
+            Dialect::Json5 => unescape_json5(&token[1..token.len() - 1])
+                .ok_or_else(|| self.error("Invalid escape sequence in string")),
+        }
+    }
+
+    /// Parse an object key: a string, or a bare identifier in JSON5
+    fn parse_key(&mut self) -> Result<String, JsonExtractError> {
+        if self.dialect == Dialect::Json5 && !matches!(self.peek(), Some(b'"') | Some(b'\'')) {
+            let len = self.src[self.pos..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(self.src.len() - self.pos);
+            let key = &self.src[self.pos..self.pos + len];
+            if !is_identifier(key) {
+                return Err(self.error("Expected object key"));
+            }
+            self.pos += len;
+            self.bare_keys += 1;
+            return Ok(key.to_string());
+        }
+        self.quoted_keys += 1;
+        self.parse_string()
+    }
+
+    fn parse_literal(&mut self) -> Result<Node, JsonExtractError> {
+        let start = self.pos;
+        let len = self.src[start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+            .unwrap_or(self.src.len() - start);
+        self.pos += len;
+        let token = &self.src[start..self.pos];
+        let value = match self.dialect {
+            Dialect::Jsonc => serde_json::from_str(token).ok(),
+            Dialect::Json5 => json5_literal(token),
+        };
+        let value = value.ok_or_else(|| {
+            self.pos = start;
+            self.error("Invalid value")
+        })?;
+        Ok(Node::Scalar {
+            span: start..self.pos,
+            value,
+        })
+    }
+
+    /// Parse the entries of an object or array between `open` and `close`
+    fn parse_entries<T>(
+        &mut self,
+        (open, close): (u8, u8),
+        mut parse_entry: impl FnMut(&mut Self, Range<usize>) -> Result<T, JsonExtractError>,
+        item: impl Fn(&mut T) -> &mut Item,
+    ) -> Result<Container<T>, JsonExtractError> {
+        let start = self.pos;
+        self.expect(open)?;
+        let mut entries = Vec::new();
+        let mut trailing_comma = false;
+
+        let tail = loop {
+            let leading_start = self.pos;
+            self.skip_trivia()?;
+            if self.peek() == Some(close) {
+                break leading_start..self.pos;
+            }
+            if !entries.is_empty() && !trailing_comma {
+                return Err(self.error("Expected ',' or closing bracket"));
+            }
+
+            let mut entry = parse_entry(self, leading_start..self.pos)?;
+            let value_end = self.pos;
+            self.skip_trivia()?;
+            let entry_item = item(&mut entry);
+            if self.peek() == Some(b',') {
+                entry_item.before_comma = value_end..self.pos;
+                self.pos += 1;
+                trailing_comma = true;
+            } else {
+                entry_item.before_comma = value_end..value_end;
+                self.pos = value_end;
+                trailing_comma = false;
+            }
+            entry_item.trailing = self.same_line_trivia()?;
+            entries.push(entry);
+        };
+        self.expect(close)?;
+
+        Ok(Container {
+            span: start..self.pos,
+            entries,
+            trailing_comma,
+            tail,
+        })
+    }
+
+    fn parse_object(&mut self) -> Result<Node, JsonExtractError> {
+        let container = self.parse_entries(
+            (b'{', b'}'),
+            |parser, leading| {
+                let key = parser.parse_key()?;
+                let key_end = parser.pos;
+                parser.skip_trivia()?;
+                parser.expect(b':')?;
+                parser.skip_trivia()?;
+                let colon = key_end..parser.pos;
+                let value = parser.parse_value()?;
+                Ok(Member {
+                    key,
+                    leading,
+                    colon,
+                    item: Item::new(value),
+                })
+            },
+            |member| &mut member.item,
+        )?;
+        Ok(Node::Object(container))
+    }
+
+    fn parse_array(&mut self) -> Result<Node, JsonExtractError> {
+        let container = self.parse_entries(
+            (b'[', b']'),
+            |parser, leading| {
+                let mut item = Item::new(parser.parse_value()?);
+                item.leading = leading;
+                Ok(item)
+            },
+            |item| item,
+        )?;
+        Ok(Node::Array(container))
+    }
+}
+
+/// Decode a JSON5 literal: JSON numbers plus hex, leading or trailing
+/// decimal points, an explicit `+` sign, `Infinity` and `NaN`
+///
+/// `Infinity` and `NaN` have no JSON equivalent and are read as [`NON_FINITE`]
+/// strings.
+fn json5_literal(token: &str) -> Option<JsonValue> {
+    let (negative, digits) = match token.as_bytes().first() {
+        Some(b'-') => (true, &token[1..]),
+        Some(b'+') => (false, &token[1..]),
+        _ => (false, token),
+    };
+    match digits {
+        "Infinity" | "NaN" => return Some(JsonValue::String(format!("{}{}", NON_FINITE, token))),
+        _ if digits.starts_with("0x") || digits.starts_with("0X") => {
+            let magnitude = i64::from_str_radix(&digits[2..], 16).ok()?;
+            return Some(JsonValue::from(if negative {
+                -magnitude
+            } else {
+                magnitude
+            }));
+        }
+        _ => {}
+    }
+    if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        // true, false and null (a sign is not allowed there)
+        return (digits.len() == token.len())
+            .then(|| serde_json::from_str(token).ok())
+            .flatten();
+    }
+
+    let mut normalized = String::from(if negative { "-" } else { "" });
+    if digits.starts_with('.') {
+        normalized.push('0');
+    }
+    normalized.push_str(digits);
+    if normalized.ends_with('.') {
+        normalized.push('0');
+    }
+    if let Some(exponent) = normalized.find(['e', 'E']) {
+        if normalized[..exponent].ends_with('.') {
+            normalized.insert(exponent, '0');
+        }
+    }
+    serde_json::from_str(&normalized).ok()
+}
+
+/// Decode the body of a JSON5 string (without its quotes)
+fn unescape_json5(body: &str) -> Option<String> {
+    let mut output = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'b' => output.push('\u{8}'),
+            'f' => output.push('\u{c}'),
+            'n' => output.push('\n'),
+            'r' => output.push('\r'),
+            't' => output.push('\t'),
+            'v' => output.push('\u{b}'),
+            '0' => output.push('\0'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                output.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let unit = u32::from_str_radix(&hex, 16).ok()?;
+                let code = if (0xd800..0xdc00).contains(&unit) {
+                    // Surrogate pair: a second \uXXXX must follow
+                    let rest: String = chars.by_ref().take(6).collect();
+                    let low = u32::from_str_radix(rest.strip_prefix("\\u")?, 16).ok()?;
+                    0x10000 + ((unit - 0xd800) << 10) + (low.checked_sub(0xdc00)?)
+                } else {
+                    unit
+                };
+                output.push(char::from_u32(code)?);
+            }
+            // Line continuation
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '\r' => {
+                if chars.clone().next() == Some('\n') {
+                    chars.next();
+                }
+            }
+            other => output.push(other),
+        }
+    }
+    Some(output)
+}
+
+impl Item {
+    fn new(value: Node) -> Self {
+        Self {
+            leading: 0..0,
+            value,
+            before_comma: 0..0,
+            trailing: 0..0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TSCONFIG: &str = r#"{
+  // Compiler settings
+  "compilerOptions": {
+    "target": "es2020", // keep in sync with node
+    /* strictness */
+    "strict": true,
+  },
+  "include": ["src"],
+}
+"#;
+
+    #[test]
+    fn test_parse_jsonc() {
+        assert_eq!(
+            parse(TSCONFIG, "tsconfig.json").unwrap(),
+            json!({"compilerOptions": {"target": "es2020", "strict": true}, "include": ["src"]})
+        );
+        assert!(parse("{\"a\": 1 /* open", "x.jsonc").is_err());
+        assert!(parse("{\"a\": 1 \"b\": 2}", "x.jsonc").is_err());
+    }
+
+    #[test]
+    fn test_write_preserves_comments() {
+        let mut value = parse(TSCONFIG, "tsconfig.json").unwrap();
+        value["compilerOptions"]["target"] = json!("es2022");
+        value["compilerOptions"]["outDir"] = json!("dist");
+        value["include"].as_array_mut().unwrap().push(json!("test"));
+
+        assert_eq!(
+            write(TSCONFIG, &value).unwrap(),
+            r#"{
+  // Compiler settings
+  "compilerOptions": {
+    "target": "es2022", // keep in sync with node
+    /* strictness */
+    "strict": true,
+    "outDir": "dist",
+  },
+  "include": ["src", "test"],
+}
+"#
+        );
+        assert_eq!(
+            write(TSCONFIG, &parse(TSCONFIG, "").unwrap()).unwrap(),
+            TSCONFIG
+        );
+    }
+
+    #[test]
+    fn test_write_reorders_with_comments() {
+        let original = "{\n  \"b\": 1, // bee\n  // about a\n  \"a\": 2\n}";
+        let value = json!({"a": 2, "b": 1, "c": {"d": [1]}});
+
+        assert_eq!(
+            write(original, &value).unwrap(),
+            "{\n  // about a\n  \"a\": 2,\n  \"b\": 1, // bee\n  \"c\": {\n    \"d\": [\n      1\n    ]\n  }\n}"
+        );
+    }
+}
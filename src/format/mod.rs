@@ -0,0 +1,192 @@
+//! Document formats
+//!
+//! Selects how input text is parsed and how edits are written back, based on
+//! the file name or an explicit choice on the command line. Every format is
+//! handled by a [`Backend`] that converts documents to and from JSON values,
+//! so the same field paths work on all of them.
+
+pub mod json5;
+pub mod jsonc;
+pub mod toml;
+pub mod xcli;
+pub mod yaml;
+
+use serde_json::Value as JsonValue;
+use std::path::Path;
+
+use crate::error::JsonExtractError;
+use crate::source::parse_json;
+
+/// File names that are conventionally JSON with comments wherever they are
+///
+/// VS Code's `settings.json`, `launch.json`, `tasks.json` and friends are too
+/// generic to list here; they are JSONC only inside `.vscode/`.
+const JSONC_FILE_NAMES: &[&str] = &[
+    "tsconfig.json",
+    "jsconfig.json",
+    "devcontainer.json",
+    ".devcontainer.json",
+    ".eslintrc.json",
+    ".babelrc",
+    "tslint.json",
+];
+
+/// Parser and writer for one document syntax
+pub trait Backend: Sync {
+    /// Parse document text; `source` names the document in error messages
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError>;
+
+    /// Serialize an edited value, keeping the layout and comments of
+    /// `original` where the syntax allows
+    fn write(&self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError>;
+
+    /// Serialize a value as a new document in this format's usual style
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError>;
+}
+
+/// Strict JSON, written back pretty-printed
+pub struct JsonBackend;
+
+impl Backend for JsonBackend {
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse_json(content, source)
+    }
+
+    fn write(&self, _original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        self.emit(value)
+    }
+
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        Ok(serde_json::to_string_pretty(value)?)
+    }
+}
+
+/// Syntax of a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Strict JSON
+    Json,
+    /// JSON with comments and trailing commas
+    Jsonc,
+    /// JSON5 (unquoted keys, single quotes, hex numbers, ...)
+    Json5,
+    /// YAML (a single document)
+    Yaml,
+    /// TOML
+    Toml,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "jsonc" => Ok(Self::Jsonc),
+            "json5" => Ok(Self::Json5),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
+    }
+}
+
+impl Format {
+    /// Detect the format from a file name, falling back to strict JSON
+    ///
+    /// `*.yaml`, `*.yml`, `*.toml` and `*.json5` select their format;
+    /// `*.jsonc`, `tsconfig*.json`, `.vscode/*.json` and other well-known
+    /// configuration files are treated as JSONC.
+    pub fn detect(path: &str) -> Self {
+        let path = Path::new(path);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let in_vscode = path
+            .parent()
+            .and_then(Path::file_name)
+            .is_some_and(|dir| dir == ".vscode");
+
+        if name.ends_with(".yaml") || name.ends_with(".yml") {
+            Format::Yaml
+        } else if name.ends_with(".toml") {
+            Format::Toml
+        } else if name.ends_with(".json5") {
+            Format::Json5
+        } else if name.ends_with(".jsonc")
+            || (name.starts_with("tsconfig") && name.ends_with(".json"))
+            || JSONC_FILE_NAMES.contains(&name.as_str())
+            || (in_vscode && name.ends_with(".json"))
+        {
+            Format::Jsonc
+        } else {
+            Format::Json
+        }
+    }
+
+    /// Use the explicit format if given, otherwise detect it from `path`
+    pub fn resolve(format: Option<Format>, path: &str) -> Self {
+        format.unwrap_or_else(|| Self::detect(path))
+    }
+
+    /// Display name of the format
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Jsonc => "JSONC",
+            Format::Json5 => "JSON5",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+        }
+    }
+
+    /// Backend that reads and writes this format
+    pub fn backend(self) -> &'static dyn Backend {
+        match self {
+            Format::Json => &JsonBackend,
+            Format::Jsonc => &jsonc::JsoncBackend,
+            Format::Json5 => &json5::Json5Backend,
+            Format::Yaml => &yaml::YamlBackend,
+            Format::Toml => &toml::TomlBackend,
+        }
+    }
+
+    /// Parse document text; `source` names the document in error messages
+    pub fn parse(self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        self.backend().parse(content, source)
+    }
+
+    /// Serialize an edited value, keeping the layout of `original` where the format allows
+    pub fn write(self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        self.backend().write(original, value)
+    }
+
+    /// Serialize a value as a new document
+    pub fn emit(self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        self.backend().emit(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Format::detect("package.json"), Format::Json);
+        assert_eq!(Format::detect("app/tsconfig.build.json"), Format::Jsonc);
+        assert_eq!(Format::detect(".vscode/settings.json"), Format::Jsonc);
+        assert_eq!(Format::detect("app/.vscode/tasks.json"), Format::Jsonc);
+        assert_eq!(Format::detect("config/settings.json"), Format::Json);
+        assert_eq!(Format::detect("tasks.json"), Format::Json);
+        assert_eq!(Format::detect("config.jsonc"), Format::Jsonc);
+        assert_eq!(Format::detect(".renovaterc.json5"), Format::Json5);
+        assert_eq!(Format::detect(".github/workflows/ci.yml"), Format::Yaml);
+        assert_eq!(Format::detect("Cargo.toml"), Format::Toml);
+        assert_eq!(
+            Format::resolve(Some(Format::Jsonc), "a.json"),
+            Format::Jsonc
+        );
+    }
+}
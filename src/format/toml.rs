@@ -0,0 +1,348 @@
+//! TOML
+//!
+//! Documents are edited through `toml_edit`, so comments, whitespace and the
+//! layout of untouched keys survive in-place edits. Tables become JSON
+//! objects; datetimes, `inf` and `nan` have no JSON equivalent and are read as
+//! strings.
+
+use serde_json::{Map, Value as JsonValue};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::error::JsonExtractError;
+use crate::get::utils::value_type_name;
+use crate::path::{child_path, index_path};
+use crate::source::display_name;
+
+/// TOML backend
+pub struct TomlBackend;
+
+impl super::Backend for TomlBackend {
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse(content, source)
+    }
+
+    fn write(&self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        write(original, value)
+    }
+
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        emit(value)
+    }
+}
+
+/// Parse TOML text into a JSON object
+pub fn parse(content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+    Ok(table_to_json(parse_document(content, source)?.as_table()))
+}
+
+/// Serialize `value` by editing `original`, preserving comments and formatting
+///
+/// Fails for values TOML cannot represent: a non-object document root, null,
+/// and integers outside the signed 64-bit range.
+pub fn write(original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+    let mut document = parse_document(original, "<original>")?;
+    update_table(document.as_table_mut(), root_table(value)?);
+    Ok(document.to_string())
+}
+
+/// Serialize `value` as a new document
+pub fn emit(value: &JsonValue) -> Result<String, JsonExtractError> {
+    let mut document = DocumentMut::new();
+    update_table(document.as_table_mut(), root_table(value)?);
+    Ok(document.to_string())
+}
+
+/// Root table of a document, after checking every value can be written
+fn root_table(value: &JsonValue) -> Result<&Map<String, JsonValue>, JsonExtractError> {
+    let map = value.as_object().ok_or_else(|| {
+        JsonExtractError::InvalidValueType(format!(
+            "a TOML document must be a table, not {}",
+            value_type_name(value)
+        ))
+    })?;
+    for (key, value) in map {
+        check_representable(value, &child_path("", key))?;
+    }
+    Ok(map)
+}
+
+/// Report the first value TOML cannot represent, with its field path
+fn check_representable(value: &JsonValue, path: &str) -> Result<(), JsonExtractError> {
+    let unsupported = |reason: String| {
+        Err(JsonExtractError::InvalidValueType(format!(
+            "{} at {}",
+            reason, path
+        )))
+    };
+    match value {
+        JsonValue::Null => unsupported("TOML has no null value".to_string()),
+        JsonValue::Number(n) if n.is_u64() && n.as_i64().is_none() => {
+            unsupported(format!("{} is too large for a TOML integer", n))
+        }
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, item)| check_representable(item, &index_path(path, i))),
+        JsonValue::Object(map) => map
+            .iter()
+            .try_for_each(|(key, value)| check_representable(value, &child_path(path, key))),
+        _ => Ok(()),
+    }
+}
+
+fn parse_document(content: &str, source: &str) -> Result<DocumentMut, JsonExtractError> {
+    content.parse::<DocumentMut>().map_err(|e| {
+        let error = match e.span() {
+            Some(span) => {
+                let consumed = &content[..span.start.min(content.len())];
+                let line = consumed.matches('\n').count() + 1;
+                let column = consumed.len() - consumed.rfind('\n').map_or(0, |i| i + 1) + 1;
+                format!("{} at line {} column {}", e.message().trim(), line, column)
+            }
+            None => e.message().trim().to_string(),
+        };
+        JsonExtractError::InvalidDocument {
+            format: "TOML".to_string(),
+            file: display_name(source).to_string(),
+            error,
+        }
+    })
+}
+
+fn table_to_json(table: &Table) -> JsonValue {
+    JsonValue::Object(
+        table
+            .iter()
+            .filter(|(_, item)| !item.is_none())
+            .map(|(key, item)| (key.to_string(), item_to_json(item)))
+            .collect(),
+    )
+}
+
+fn item_to_json(item: &Item) -> JsonValue {
+    match item {
+        Item::None => JsonValue::Null,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => table_to_json(table),
+        Item::ArrayOfTables(tables) => JsonValue::Array(tables.iter().map(table_to_json).collect()),
+    }
+}
+
+fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::String(s) => JsonValue::String(s.value().clone()),
+        Value::Integer(i) => JsonValue::from(*i.value()),
+        Value::Float(f) => serde_json::Number::from_f64(*f.value()).map_or_else(
+            || JsonValue::String(f.to_string().trim().to_string()),
+            JsonValue::Number,
+        ),
+        Value::Boolean(b) => JsonValue::Bool(*b.value()),
+        Value::Datetime(d) => JsonValue::String(d.value().to_string()),
+        Value::Array(array) => JsonValue::Array(array.iter().map(value_to_json).collect()),
+        Value::InlineTable(table) => JsonValue::Object(
+            table
+                .iter()
+                .map(|(key, value)| (key.to_string(), value_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Make `table` hold `map`, keeping the formatting of unchanged entries
+///
+/// Values must have passed [`check_representable`].
+fn update_table(table: &mut Table, map: &Map<String, JsonValue>) {
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !map.contains_key(key))
+        .collect();
+    for key in removed {
+        table.remove(&key);
+    }
+
+    for (key, value) in map {
+        match table.get_mut(key) {
+            Some(item) if !item.is_none() => update_item(item, value),
+            _ => {
+                table.insert(key, new_item(value));
+            }
+        }
+    }
+
+    let order: Vec<&String> = map.keys().collect();
+    let position = |key: &str| order.iter().position(|k| *k == key);
+    if !table.iter().map(|(key, _)| position(key)).is_sorted() {
+        table.sort_values_by(|a, _, b, _| position(a).cmp(&position(b)));
+    }
+}
+
+fn update_item(item: &mut Item, value: &JsonValue) {
+    if item_to_json(item) == *value {
+        return;
+    }
+    match (item, value) {
+        (Item::Table(table), JsonValue::Object(map)) => update_table(table, map),
+        (Item::ArrayOfTables(tables), JsonValue::Array(items))
+            if items.iter().all(JsonValue::is_object) =>
+        {
+            while tables.len() > items.len() {
+                tables.remove(tables.len() - 1);
+            }
+            for (i, map) in items.iter().filter_map(JsonValue::as_object).enumerate() {
+                match tables.get_mut(i) {
+                    Some(table) => update_table(table, map),
+                    None => tables.push(new_table(map)),
+                }
+            }
+        }
+        (Item::Value(current), value) => update_value(current, value),
+        (item, value) => *item = new_item(value),
+    }
+}
+
+fn update_value(current: &mut Value, value: &JsonValue) {
+    if value_to_json(current) == *value {
+        return;
+    }
+    match (current, value) {
+        (Value::InlineTable(table), JsonValue::Object(map)) => {
+            let removed: Vec<String> = table
+                .iter()
+                .map(|(key, _)| key.to_string())
+                .filter(|key| !map.contains_key(key))
+                .collect();
+            for key in removed {
+                table.remove(&key);
+            }
+            for (key, value) in map {
+                match table.get_mut(key) {
+                    Some(current) => update_value(current, value),
+                    None => {
+                        table.insert(key, to_value(value));
+                    }
+                }
+            }
+        }
+        (Value::Array(array), JsonValue::Array(items)) => {
+            while array.len() > items.len() {
+                array.remove(array.len() - 1);
+            }
+            for (i, item) in items.iter().enumerate() {
+                match array.get_mut(i) {
+                    Some(current) => update_value(current, item),
+                    None => array.push(to_value(item)),
+                }
+            }
+        }
+        (current, value) => {
+            // Keep the whitespace and comments around the replaced value
+            let decor = current.decor().clone();
+            *current = to_value(value);
+            *current.decor_mut() = decor;
+        }
+    }
+}
+
+/// Item for a key added to a standard table
+fn new_item(value: &JsonValue) -> Item {
+    match value {
+        JsonValue::Object(map) => Item::Table(new_table(map)),
+        JsonValue::Array(items) if !items.is_empty() && items.iter().all(JsonValue::is_object) => {
+            let mut tables = ArrayOfTables::new();
+            for map in items.iter().filter_map(JsonValue::as_object) {
+                tables.push(new_table(map));
+            }
+            Item::ArrayOfTables(tables)
+        }
+        value => Item::Value(to_value(value)),
+    }
+}
+
+fn new_table(map: &Map<String, JsonValue>) -> Table {
+    let mut table = Table::new();
+    for (key, value) in map {
+        table.insert(key, new_item(value));
+    }
+    // Skip the `[header]` of tables that only hold other tables
+    table.set_implicit(!table.is_empty() && table.iter().all(|(_, item)| !item.is_value()));
+    table
+}
+
+/// Inline TOML value for a JSON value other than null
+fn to_value(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => unreachable!("null is rejected by check_representable"),
+        JsonValue::Bool(b) => Value::from(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Value::from(i),
+            None => Value::from(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => Value::from(s.as_str()),
+        JsonValue::Array(items) => Value::Array(items.iter().map(to_value).collect::<Array>()),
+        JsonValue::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, value) in map {
+                table.insert(key, to_value(value));
+            }
+            Value::InlineTable(table)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CARGO: &str = r#"# Package manifest
+[package]
+name = "demo"   # crate name
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+"#;
+
+    #[test]
+    fn test_parse_toml() {
+        assert_eq!(
+            parse(CARGO, "Cargo.toml").unwrap(),
+            json!({
+                "package": {"name": "demo", "version": "0.1.0"},
+                "dependencies": {"serde": {"version": "1.0", "features": ["derive"]}},
+            })
+        );
+        assert!(parse("[package\nname = 1", "Cargo.toml")
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid TOML syntax in Cargo.toml"));
+    }
+
+    #[test]
+    fn test_write_preserves_comments() {
+        let mut value = parse(CARGO, "Cargo.toml").unwrap();
+        value["package"]["name"] = json!("renamed");
+        value["package"]["edition"] = json!("2021");
+        value["dependencies"]["serde"]["version"] = json!("1.1");
+
+        assert_eq!(
+            write(CARGO, &value).unwrap(),
+            r#"# Package manifest
+[package]
+name = "renamed"   # crate name
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+serde = { version = "1.1", features = ["derive"] }
+"#
+        );
+
+        value["package"]["license"] = JsonValue::Null;
+        assert_eq!(
+            write(CARGO, &value).unwrap_err().to_string(),
+            "Invalid value type: TOML has no null value at package.license"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Command-line arguments shared by the commands that read documents
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches};
+
+use super::Format;
+
+/// `--format FORMAT`, overriding the format detected from the file name
+pub fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_name("FORMAT")
+        .help("Input format (default: detected from the file name)")
+        .value_parser(["json", "jsonc", "json5", "yaml", "yml", "toml"])
+}
+
+/// Document format chosen with `--format`, or with `--jsonc` on commands that
+/// have it (None to detect)
+pub fn selected_format(matches: &ArgMatches) -> Result<Option<Format>> {
+    match matches.get_one::<String>("format") {
+        Some(name) => Ok(Some(name.parse().map_err(anyhow::Error::msg)?)),
+        None => {
+            let jsonc = matches.try_get_one::<bool>("jsonc").ok().flatten();
+            Ok((jsonc == Some(&true)).then_some(Format::Jsonc))
+        }
+    }
+}
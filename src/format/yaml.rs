@@ -0,0 +1,1257 @@
+//! YAML
+//!
+//! Documents are read with yaml-rust2 and converted to JSON values. Edits are
+//! written back by patching the original text: untouched entries, comments and
+//! blank lines are kept verbatim, changed scalars are replaced in place and new
+//! keys follow the indentation of their siblings. Anchored and aliased nodes
+//! are kept verbatim, so only edits that would change them are refused.
+//! Documents the patcher does not understand (tags, multi-line plain scalars,
+//! ...) are rejected with an error naming the construct rather than rewritten
+//! without their comments.
+
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashSet;
+use std::ops::Range;
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::error::JsonExtractError;
+use crate::path::{child_path, index_path};
+use crate::source::display_name;
+
+/// YAML backend
+pub struct YamlBackend;
+
+impl super::Backend for YamlBackend {
+    fn parse(&self, content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse(content, source)
+    }
+
+    fn write(&self, original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+        write(original, value)
+    }
+
+    fn emit(&self, value: &JsonValue) -> Result<String, JsonExtractError> {
+        Ok(emit(value))
+    }
+}
+
+/// Parse a single YAML document into a JSON value
+///
+/// Aliases are expanded and merge keys (`<<`) resolved; mapping keys must be
+/// scalars and are converted to strings. An empty document is `null`.
+pub fn parse(content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+    let invalid = |error: String| JsonExtractError::InvalidDocument {
+        format: "YAML".to_string(),
+        file: display_name(source).to_string(),
+        error,
+    };
+    let documents = YamlLoader::load_from_str(content).map_err(|e| invalid(e.to_string()))?;
+    match documents.as_slice() {
+        [] => Ok(JsonValue::Null),
+        [document] => to_json(document).map_err(invalid),
+        _ => Err(invalid("multiple documents are not supported".to_string())),
+    }
+}
+
+/// Serialize `value` by patching `original`, keeping its comments
+///
+/// Fails with [`JsonExtractError::UnsupportedDocument`] when `original` uses
+/// a construct the patcher cannot keep, such as tags, or when the edit lands
+/// inside an anchored or aliased node.
+pub fn write(original: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+    let unsupported = |reason: String| JsonExtractError::UnsupportedDocument {
+        format: "YAML".to_string(),
+        reason,
+    };
+    let old = parse(original, "<original>")?;
+    if old == *value {
+        return Ok(original.to_string());
+    }
+
+    let mut scanner = Scanner::new(original);
+    if scanner.is_empty() {
+        // Only comments and markers: keep them and append the new content
+        let mut out = original.to_string();
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        return Ok(out + &emit(value));
+    }
+    let document = scanner
+        .document()
+        .filter(|document| document.root.matches(&old))
+        .ok_or_else(|| {
+            unsupported(format!(
+                "{} cannot be edited without losing formatting",
+                scanner.unsupported.unwrap_or("this layout")
+            ))
+        })?;
+    check_shared(&document.root, &old, value, "").map_err(unsupported)?;
+    let renderer = Renderer {
+        src: original,
+        style: Style::detect(&document.root),
+    };
+    let output = renderer.document(&document, &old, value);
+
+    // Removing an anchor or overriding a merged key can change what other
+    // nodes read, so documents that share nodes are checked once more
+    if scanner.shared && parse(&output, "<output>").ok().as_ref() != Some(value) {
+        return Err(unsupported(
+            "this edit changes nodes shared through anchors".to_string(),
+        ));
+    }
+    Ok(output)
+}
+
+/// Serialize `value` as a new block-style document
+pub fn emit(value: &JsonValue) -> String {
+    Renderer {
+        src: "",
+        style: Style::default(),
+    }
+    .emit(value)
+}
+
+fn to_json(yaml: &Yaml) -> Result<JsonValue, String> {
+    Ok(match yaml {
+        Yaml::Null => JsonValue::Null,
+        Yaml::Boolean(b) => JsonValue::Bool(*b),
+        Yaml::Integer(i) => JsonValue::from(*i),
+        Yaml::Real(text) => yaml
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map_or_else(|| JsonValue::String(text.clone()), JsonValue::Number),
+        Yaml::String(s) => JsonValue::String(s.clone()),
+        Yaml::Array(items) => {
+            JsonValue::Array(items.iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Yaml::Hash(hash) => {
+            // Keys written in the mapping win over those of its merge keys
+            let explicit = hash
+                .keys()
+                .filter(|key| !is_merge_key(key))
+                .map(key_string)
+                .collect::<Result<HashSet<_>, _>>()?;
+            let mut map = Map::new();
+            for (key, value) in hash {
+                if !is_merge_key(key) {
+                    map.insert(key_string(key)?, to_json(value)?);
+                    continue;
+                }
+                let sources = match value {
+                    Yaml::Array(sources) => sources.as_slice(),
+                    source => std::slice::from_ref(source),
+                };
+                for source in sources {
+                    let JsonValue::Object(source) = to_json(source)? else {
+                        return Err(
+                            "a merge key (<<) needs a mapping or a list of mappings".to_string()
+                        );
+                    };
+                    for (key, value) in source {
+                        if !explicit.contains(&key) && !map.contains_key(&key) {
+                            map.insert(key, value);
+                        }
+                    }
+                }
+            }
+            JsonValue::Object(map)
+        }
+        Yaml::Alias(_) | Yaml::BadValue => return Err("unresolved alias".to_string()),
+    })
+}
+
+fn is_merge_key(key: &Yaml) -> bool {
+    matches!(key, Yaml::String(s) if s == "<<")
+}
+
+fn key_string(key: &Yaml) -> Result<String, String> {
+    match key {
+        Yaml::String(s) | Yaml::Real(s) => Ok(s.clone()),
+        Yaml::Integer(i) => Ok(i.to_string()),
+        Yaml::Boolean(b) => Ok(b.to_string()),
+        Yaml::Null => Ok("null".to_string()),
+        _ => Err("only scalar mapping keys are supported".to_string()),
+    }
+}
+
+/// Whether `text` is blank or only a comment
+fn is_trivia(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.starts_with('#')
+}
+
+fn is_dash(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+/// Length of the quoted scalar at the start of `text`, including both quotes
+fn quoted_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let quote = *bytes.first()?;
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote == b'"' => i += 1,
+            b'\'' if quote == b'\'' && bytes.get(i + 1) == Some(&b'\'') => i += 1,
+            b if b == quote => return Some(i + 1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Length of the plain scalar at the start of `text`, without a trailing comment
+fn plain_len(text: &str) -> usize {
+    let end = [text.find(" #"), text.find("\t#")]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(text.len());
+    text[..end].trim_end().len()
+}
+
+/// Split `key: value` into the key text and the offset just after the colon
+fn split_key(content: &str) -> Option<(&str, usize)> {
+    let bytes = content.as_bytes();
+    let key_end = match bytes.first()? {
+        b'"' | b'\'' => quoted_len(content)?,
+        b'[' | b'{' | b'&' | b'*' | b'!' | b'|' | b'>' | b'%' | b'@' | b'`' | b'#' | b'?' => {
+            return None
+        }
+        _ if is_dash(content) => return None,
+        _ => {
+            let colon = (0..bytes.len()).find(|&i| {
+                bytes[i] == b':' && bytes.get(i + 1).is_none_or(|b| *b == b' ' || *b == b'\t')
+            })?;
+            if content[..colon].contains(" #") {
+                return None;
+            }
+            colon
+        }
+    };
+    let after = content[key_end..].trim_start_matches(' ');
+    if !after.starts_with(':') {
+        return None;
+    }
+    let colon = content.len() - after.len();
+    if bytes
+        .get(colon + 1)
+        .is_some_and(|b| *b != b' ' && *b != b'\t')
+    {
+        return None;
+    }
+    Some((content[..key_end].trim_end(), colon + 1))
+}
+
+/// Decode a key the way the parser does, so scanned and parsed keys compare equal
+fn decode_key(text: &str) -> Option<String> {
+    let documents = YamlLoader::load_from_str(text).ok()?;
+    match documents.as_slice() {
+        [key] => key_string(key).ok(),
+        _ => None,
+    }
+}
+
+/// One line of the original text
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    /// Byte offset of the first character
+    start: usize,
+    /// Byte offset after the line break
+    end: usize,
+    /// Number of leading spaces
+    indent: usize,
+    /// Text without the line break
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn content(&self) -> &str {
+        &self.text[self.indent..]
+    }
+
+    fn is_trivia(&self) -> bool {
+        is_trivia(self.text)
+    }
+}
+
+/// Node of the block structure of a document
+enum Node {
+    /// Block mapping
+    Mapping(Vec<Entry>),
+    /// Block sequence
+    Sequence(Vec<Entry>),
+    /// Scalar or flow collection on the indicator line (empty for null)
+    Scalar(Range<usize>),
+    /// Block scalar or flow collection spanning several lines
+    Block,
+    /// Node with an anchor (`&name`), kept verbatim
+    Anchored,
+    /// Alias (`*name`), kept verbatim
+    Alias,
+}
+
+/// Mapping entry or sequence item
+struct Entry {
+    /// Mapping key; None for sequence items
+    key: Option<String>,
+    /// Text of the entry, from its leading comments to the end of its last line
+    span: Range<usize>,
+    /// Offset just after the `key:` or `-` indicator
+    indicator_end: usize,
+    /// Column of the key or dash
+    indent: usize,
+    value: Node,
+}
+
+impl Entry {
+    /// Whether this is a merge key (`<<: *name`)
+    fn is_merge(&self) -> bool {
+        self.key.as_deref() == Some("<<")
+    }
+}
+
+impl Node {
+    /// Whether the scanned structure agrees with the parsed value
+    fn matches(&self, value: &JsonValue) -> bool {
+        match (self, value) {
+            // Merged keys are not written out, so only the others are compared
+            (Node::Mapping(entries), JsonValue::Object(map))
+                if entries.iter().any(Entry::is_merge) =>
+            {
+                entries
+                    .iter()
+                    .filter(|entry| !entry.is_merge())
+                    .all(|entry| {
+                        entry
+                            .key
+                            .as_ref()
+                            .and_then(|key| map.get(key))
+                            .is_some_and(|value| entry.value.matches(value))
+                    })
+            }
+            (Node::Mapping(entries), JsonValue::Object(map)) => {
+                entries.len() == map.len()
+                    && entries.iter().zip(map).all(|(entry, (key, value))| {
+                        entry.key.as_ref() == Some(key) && entry.value.matches(value)
+                    })
+            }
+            (Node::Sequence(entries), JsonValue::Array(items)) => {
+                entries.len() == items.len()
+                    && entries
+                        .iter()
+                        .zip(items)
+                        .all(|(entry, item)| entry.value.matches(item))
+            }
+            (Node::Scalar(_) | Node::Block | Node::Anchored | Node::Alias, _) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Refuse edits that land inside anchored or aliased nodes, or change a key
+/// brought in by a merge key, following the matching done by [`Renderer`]
+fn check_shared(node: &Node, old: &JsonValue, new: &JsonValue, path: &str) -> Result<(), String> {
+    if old == new {
+        return Ok(());
+    }
+    match (node, old, new) {
+        (Node::Anchored, _, _) => Err(format!(
+            "{} has an anchor (&name), so its content cannot be edited",
+            path
+        )),
+        (Node::Alias, _, _) => Err(format!(
+            "{} is an alias (*name), so its content cannot be edited",
+            path
+        )),
+        (Node::Mapping(entries), JsonValue::Object(old), JsonValue::Object(new)) => {
+            for (key, value) in old {
+                let path = child_path(path, key);
+                match entries.iter().find(|entry| entry.key.as_ref() == Some(key)) {
+                    Some(entry) => {
+                        if let Some(new) = new.get(key) {
+                            check_shared(&entry.value, value, new, &path)?;
+                        }
+                    }
+                    None if new.get(key) != Some(value) => {
+                        return Err(format!(
+                            "{} comes from a merge key (<<) and cannot be edited",
+                            path
+                        ))
+                    }
+                    None => {}
+                }
+            }
+            Ok(())
+        }
+        (Node::Sequence(entries), JsonValue::Array(old), JsonValue::Array(new)) => {
+            let mut next = 0;
+            for value in new {
+                match (next..old.len()).find(|&j| old[j] == *value) {
+                    Some(j) => next = j + 1,
+                    None if next < old.len() => {
+                        check_shared(
+                            &entries[next].value,
+                            &old[next],
+                            value,
+                            &index_path(path, next),
+                        )?;
+                        next += 1;
+                    }
+                    None => {}
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Scanned document: leading trivia, root collection and trailing trivia
+struct Document {
+    head: Range<usize>,
+    root: Node,
+    tail: Range<usize>,
+}
+
+/// Line-based scanner for the block structure of a YAML document
+///
+/// Every method returns None for constructs it does not handle, after
+/// recording the first of them in `unsupported`.
+struct Scanner<'a> {
+    src: &'a str,
+    lines: Vec<Line<'a>>,
+    /// Index of the next unconsumed line
+    next: usize,
+    /// Offset where the next entry (with its leading trivia) begins
+    pos: usize,
+    /// Description of the first construct that stopped the scan
+    unsupported: Option<&'static str>,
+    /// Whether the document has anchors, aliases or merge keys
+    shared: bool,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(src: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while start < src.len() {
+            let end = src[start..].find('\n').map_or(src.len(), |i| start + i + 1);
+            let text = src[start..end].trim_end_matches(['\n', '\r']);
+            let indent = text.len() - text.trim_start_matches(' ').len();
+            lines.push(Line {
+                start,
+                end,
+                indent,
+                text,
+            });
+            start = end;
+        }
+        Self {
+            src,
+            lines,
+            next: 0,
+            pos: 0,
+            unsupported: None,
+            shared: false,
+        }
+    }
+
+    /// Record `what` as the reason the scan stops
+    fn unsupported<T>(&mut self, what: &'static str) -> Option<T> {
+        self.unsupported.get_or_insert(what);
+        None
+    }
+
+    /// Whether the text has no content besides comments and `---`/`...`
+    fn is_empty(&self) -> bool {
+        self.lines.iter().all(|line| {
+            let content = line.content();
+            line.is_trivia()
+                || ((content.starts_with("---") || content.starts_with("..."))
+                    && is_trivia(&content[3..]))
+        })
+    }
+
+    /// Index of the next line that is not blank or a comment
+    fn significant(&self) -> Option<usize> {
+        (self.next..self.lines.len()).find(|&i| !self.lines[i].is_trivia())
+    }
+
+    fn document(&mut self) -> Option<Document> {
+        let mut first = self.significant()?;
+        if let Some(rest) = self.lines[first].content().strip_prefix("---") {
+            if !is_trivia(rest) {
+                return self.unsupported("content on the --- line");
+            }
+            self.next = first + 1;
+            first = self.significant()?;
+        }
+        self.next = first;
+        self.pos = self.lines[first].start;
+        let head = 0..self.pos;
+        let root = self.node_at(first)?;
+        if self.significant().is_some() {
+            return self.unsupported("content after the root collection");
+        }
+        Some(Document {
+            head,
+            root,
+            tail: self.pos..self.src.len(),
+        })
+    }
+
+    /// Collection starting at line `i`
+    fn node_at(&mut self, i: usize) -> Option<Node> {
+        let line = self.lines[i];
+        if line.content().starts_with('\t') {
+            self.unsupported("tab indentation")
+        } else if is_dash(line.content()) {
+            self.sequence(line.indent)
+        } else if split_key(line.content()).is_some() {
+            self.mapping(line.indent, false)
+        } else {
+            self.unsupported("a scalar where a collection was expected")
+        }
+    }
+
+    /// Block mapping at column `indent`; `compact` when its first key shares
+    /// the line of a sequence dash
+    fn mapping(&mut self, indent: usize, mut compact: bool) -> Option<Node> {
+        let mut entries = Vec::new();
+        loop {
+            let i = if compact {
+                self.next
+            } else {
+                match self.significant() {
+                    Some(i) => i,
+                    None => break,
+                }
+            };
+            let line = self.lines[i];
+            let column = if compact { indent } else { line.indent };
+            let content = &line.text[column..];
+            if !compact && (column < indent || is_dash(content)) {
+                break;
+            }
+            compact = false;
+            if column > indent {
+                return self.unsupported("inconsistent indentation");
+            }
+
+            let key = split_key(content).and_then(|(key, colon)| Some((decode_key(key)?, colon)));
+            let Some((key, colon)) = key else {
+                return self.unsupported("a complex mapping key");
+            };
+            self.shared |= key == "<<";
+            let start = self.pos;
+            let indicator_end = line.start + column + colon;
+            self.next = i + 1;
+            self.pos = line.end;
+            let value = self.value(&content[colon..], indicator_end, indent, false)?;
+            entries.push(Entry {
+                key: Some(key),
+                span: start..self.pos,
+                indicator_end,
+                indent,
+                value,
+            });
+        }
+        (!entries.is_empty()).then_some(Node::Mapping(entries))
+    }
+
+    /// Block sequence with dashes at column `indent`
+    fn sequence(&mut self, indent: usize) -> Option<Node> {
+        let mut entries = Vec::new();
+        while let Some(i) = self.significant() {
+            let line = self.lines[i];
+            if line.indent < indent || !is_dash(line.content()) {
+                break;
+            }
+            if line.indent > indent {
+                return self.unsupported("inconsistent indentation");
+            }
+
+            let start = self.pos;
+            let indicator_end = line.start + indent + 1;
+            let rest = &line.content()[1..];
+            let item = rest.trim_start();
+            self.next = i + 1;
+            self.pos = line.end;
+            let value = if is_dash(item) {
+                return self.unsupported("a nested sequence on the line of its dash");
+            } else if !is_trivia(item) && split_key(item).is_some() {
+                let column = indent + 1 + rest.len() - item.len();
+                self.next = i;
+                self.pos = line.start + column;
+                self.mapping(column, true)?
+            } else {
+                self.value(rest, indicator_end, indent, true)?
+            };
+            entries.push(Entry {
+                key: None,
+                span: start..self.pos,
+                indicator_end,
+                indent,
+                value,
+            });
+        }
+        (!entries.is_empty()).then_some(Node::Sequence(entries))
+    }
+
+    /// Value following an indicator; `rest` is the text after it on its line
+    fn value(&mut self, rest: &str, offset: usize, indent: usize, item: bool) -> Option<Node> {
+        let text = rest.trim_start();
+        let start = offset + rest.len() - text.len();
+        if is_trivia(text) {
+            return match self.significant() {
+                Some(i) if self.lines[i].indent > indent => self.node_at(i),
+                Some(i)
+                    if !item
+                        && self.lines[i].indent == indent
+                        && is_dash(self.lines[i].content()) =>
+                {
+                    self.sequence(indent)
+                }
+                _ => Some(Node::Scalar(offset..offset)),
+            };
+        }
+
+        let end = match text.as_bytes()[0] {
+            b'&' => {
+                // The anchored value is scanned only to find where it ends
+                self.shared = true;
+                let len = text.find([' ', '\t']).unwrap_or(text.len());
+                self.value(&text[len..], start + len, indent, item)?;
+                return Some(Node::Anchored);
+            }
+            b'*' => {
+                self.shared = true;
+                self.scalar(start..start + plain_len(text), indent)?;
+                return Some(Node::Alias);
+            }
+            b'!' => return self.unsupported("a tag (!tag)"),
+            b'%' | b'@' | b'`' => return self.unsupported("a reserved indicator"),
+            b'|' | b'>' => return Some(self.block_scalar(indent)),
+            b'[' | b'{' => return self.flow(start, indent),
+            b'"' | b'\'' => {
+                let Some(len) = quoted_len(text) else {
+                    return self.unsupported("a multi-line quoted scalar");
+                };
+                if !is_trivia(&text[len..]) {
+                    return self.unsupported("text after a quoted scalar");
+                }
+                start + len
+            }
+            _ => start + plain_len(text),
+        };
+        self.scalar(start..end, indent)
+    }
+
+    /// Single-line value, unless the following lines continue it
+    fn scalar(&mut self, range: Range<usize>, indent: usize) -> Option<Node> {
+        match self.significant() {
+            Some(i) if self.lines[i].indent > indent => {
+                self.unsupported("a multi-line plain scalar")
+            }
+            _ => Some(Node::Scalar(range)),
+        }
+    }
+
+    /// Literal or folded block scalar whose header is on the last consumed line
+    fn block_scalar(&mut self, indent: usize) -> Node {
+        let mut last = self.next - 1;
+        for i in self.next..self.lines.len() {
+            let line = self.lines[i];
+            if line.text.trim().is_empty() {
+                continue;
+            }
+            if line.indent <= indent {
+                break;
+            }
+            last = i;
+        }
+        let line = self.lines[last];
+        self.next = last + 1;
+        self.pos = line.end;
+        Node::Block
+    }
+
+    /// Flow collection starting at `start`, possibly spanning several lines
+    fn flow(&mut self, start: usize, indent: usize) -> Option<Node> {
+        let bytes = self.src.as_bytes();
+        let mut depth = 0;
+        let mut quote = None;
+        let mut i = start;
+        let end = loop {
+            let Some(&b) = bytes.get(i) else {
+                return self.unsupported("an unterminated flow collection");
+            };
+            match quote {
+                Some(b'"') if b == b'\\' => i += 1,
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None => match b {
+                    b'"' | b'\'' => quote = Some(b),
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break i + 1;
+                        }
+                    }
+                    b'#' if bytes[i - 1].is_ascii_whitespace() => {
+                        return self.unsupported("a comment inside a flow collection")
+                    }
+                    _ => {}
+                },
+            }
+            i += 1;
+        };
+
+        let last = self.lines.iter().position(|line| end <= line.end)?;
+        let line = self.lines[last];
+        if !is_trivia(&self.src[end..line.start + line.text.len()]) {
+            return self.unsupported("text after a flow collection");
+        }
+        if last < self.next {
+            return self.scalar(start..end, indent);
+        }
+        self.next = last + 1;
+        self.pos = line.end;
+        Some(Node::Block)
+    }
+}
+
+/// Indentation used for newly written collections
+struct Style {
+    /// Indentation of a nested mapping
+    unit: usize,
+    /// Indentation of a block sequence under a mapping key (0 for
+    /// `key:\n- item`)
+    sequence: usize,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            unit: 2,
+            sequence: 2,
+        }
+    }
+}
+
+impl Style {
+    fn detect(root: &Node) -> Self {
+        fn visit(node: &Node, unit: &mut Option<usize>, sequence: &mut Option<usize>) {
+            if let Node::Mapping(entries) | Node::Sequence(entries) = node {
+                for entry in entries.iter().filter(|entry| entry.key.is_some()) {
+                    match &entry.value {
+                        Node::Mapping(children) => {
+                            unit.get_or_insert(children[0].indent - entry.indent);
+                        }
+                        Node::Sequence(children) => {
+                            sequence.get_or_insert(children[0].indent - entry.indent);
+                        }
+                        _ => {}
+                    }
+                }
+                for entry in entries {
+                    visit(&entry.value, unit, sequence);
+                }
+            }
+        }
+
+        let (mut unit, mut sequence) = (None, None);
+        visit(root, &mut unit, &mut sequence);
+        let unit = unit.or(sequence.filter(|indent| *indent > 0)).unwrap_or(2);
+        Self {
+            unit,
+            sequence: sequence.unwrap_or(unit),
+        }
+    }
+}
+
+fn pad(indent: usize) -> String {
+    " ".repeat(indent)
+}
+
+/// Append `text` to `out`, starting a new line if needed
+fn push_line(out: &mut String, text: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(text);
+}
+
+/// Whether `s` can be written without quotes and still read back as `s`
+fn is_plain(s: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`',
+    ];
+    // YAML 1.1 booleans, still read as such by many tools
+    const BOOLEANS: &[&str] = &["y", "n", "yes", "no", "on", "off"];
+
+    !s.is_empty()
+        && s.trim() == s
+        && !s.starts_with(INDICATORS)
+        && !is_dash(s)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.chars().any(char::is_control)
+        && !BOOLEANS.contains(&s.to_lowercase().as_str())
+        && matches!(
+            YamlLoader::load_from_str(s).as_deref(),
+            Ok([Yaml::String(parsed)]) if parsed == s
+        )
+}
+
+/// Scalar text for a block context
+fn scalar(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) if is_plain(s) => s.clone(),
+        JsonValue::Object(_) | JsonValue::Array(_) => flow(value),
+        other => other.to_string(),
+    }
+}
+
+fn key_text(key: &str) -> String {
+    if is_plain(key) {
+        key.to_string()
+    } else {
+        JsonValue::from(key).to_string()
+    }
+}
+
+/// Flow-style text (`[a, b]`, `{k: v}`) for a value
+fn flow(value: &JsonValue) -> String {
+    let item = |value: &JsonValue| match value {
+        JsonValue::String(s) if s.contains([',', '[', ']', '{', '}']) => value.to_string(),
+        _ => flow(value),
+    };
+    match value {
+        JsonValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(item).collect();
+            format!("[{}]", items.join(", "))
+        }
+        JsonValue::Object(map) if map.is_empty() => "{}".to_string(),
+        JsonValue::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(key, value)| {
+                    let key = JsonValue::from(key.as_str());
+                    format!("{}: {}", item(&key).trim_matches('"'), item(value))
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        scalar_value => scalar(scalar_value),
+    }
+}
+
+/// Writes edited values into the original text
+struct Renderer<'a> {
+    src: &'a str,
+    style: Style,
+}
+
+impl Renderer<'_> {
+    fn document(&self, document: &Document, old: &JsonValue, new: &JsonValue) -> String {
+        let mut body = match (&document.root, old, new) {
+            (Node::Mapping(entries), JsonValue::Object(old), JsonValue::Object(new))
+                if !new.is_empty() =>
+            {
+                self.mapping(entries, old, new)
+            }
+            (Node::Sequence(entries), JsonValue::Array(old), JsonValue::Array(new))
+                if !new.is_empty() =>
+            {
+                self.sequence(entries, old, new)
+            }
+            _ => self.emit(new),
+        };
+        if !document.tail.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        format!(
+            "{}{}{}",
+            &self.src[document.head.clone()],
+            body,
+            &self.src[document.tail.clone()]
+        )
+    }
+
+    /// Whole document written from scratch
+    fn emit(&self, value: &JsonValue) -> String {
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                format!("{}\n", self.mapping_lines(map, 0))
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                format!("{}\n", self.sequence_lines(items, 0))
+            }
+            _ => format!("{}\n", scalar(value)),
+        }
+    }
+
+    fn mapping(
+        &self,
+        entries: &[Entry],
+        old: &Map<String, JsonValue>,
+        new: &Map<String, JsonValue>,
+    ) -> String {
+        let indent = entries[0].indent;
+        let mut out = String::new();
+        // Merge keys stay before the entries that followed them
+        let mut merges = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_merge())
+            .peekable();
+        for (key, value) in new {
+            let position = entries
+                .iter()
+                .position(|entry| entry.key.as_ref() == Some(key));
+            if position.is_none() && old.contains_key(key) {
+                // Brought in by a merge key
+                continue;
+            }
+            while let Some((_, merge)) =
+                merges.next_if(|(j, _)| position.is_none_or(|position| *j < position))
+            {
+                push_line(&mut out, &self.src[merge.span.clone()]);
+            }
+            let text = match position {
+                Some(j) => self.entry(&entries[j], &old[key], value),
+                None => format!(
+                    "{}{}:{}\n",
+                    pad(indent),
+                    key_text(key),
+                    self.fresh(value, indent, false)
+                ),
+            };
+            push_line(&mut out, &text);
+        }
+        for (_, merge) in merges {
+            push_line(&mut out, &self.src[merge.span.clone()]);
+        }
+        out
+    }
+
+    /// Items are matched to the original entries in order, so comments stay
+    /// with items that are kept when others are removed
+    fn sequence(&self, entries: &[Entry], old: &[JsonValue], new: &[JsonValue]) -> String {
+        let indent = entries[0].indent;
+        let mut out = String::new();
+        let mut next = 0;
+        for value in new {
+            let text = match (next..old.len()).find(|&j| old[j] == *value) {
+                Some(j) => {
+                    next = j + 1;
+                    self.src[entries[j].span.clone()].to_string()
+                }
+                None if next < old.len() => {
+                    next += 1;
+                    self.entry(&entries[next - 1], &old[next - 1], value)
+                }
+                None => format!("{}-{}\n", pad(indent), self.fresh(value, indent, true)),
+            };
+            push_line(&mut out, &text);
+        }
+        out
+    }
+
+    fn entry(&self, entry: &Entry, old: &JsonValue, new: &JsonValue) -> String {
+        let span = &entry.span;
+        if old == new {
+            return self.src[span.clone()].to_string();
+        }
+        match (&entry.value, old, new) {
+            (Node::Mapping(children), JsonValue::Object(old), JsonValue::Object(new))
+                if !new.is_empty()
+                    && (self.at_line_start(children[0].span.start)
+                        || children[0].is_merge()
+                        || new.keys().next() == children[0].key.as_ref()) =>
+            {
+                format!(
+                    "{}{}",
+                    &self.src[span.start..children[0].span.start],
+                    self.mapping(children, old, new)
+                )
+            }
+            (Node::Sequence(children), JsonValue::Array(old), JsonValue::Array(new))
+                if !new.is_empty() =>
+            {
+                format!(
+                    "{}{}",
+                    &self.src[span.start..children[0].span.start],
+                    self.sequence(children, old, new)
+                )
+            }
+            (Node::Scalar(range), _, _) => match self.inline(&self.src[range.clone()], new) {
+                Some(text) => format!(
+                    "{}{}{}{}",
+                    &self.src[span.start..range.start],
+                    if range.is_empty() { " " } else { "" },
+                    text,
+                    &self.src[range.end..span.end]
+                ),
+                None => self.replace(entry, new),
+            },
+            _ => self.replace(entry, new),
+        }
+    }
+
+    /// Entry with its value written from scratch after the indicator
+    fn replace(&self, entry: &Entry, new: &JsonValue) -> String {
+        format!(
+            "{}{}\n",
+            &self.src[entry.span.start..entry.indicator_end],
+            self.fresh(new, entry.indent, entry.key.is_none())
+        )
+    }
+
+    fn at_line_start(&self, offset: usize) -> bool {
+        offset == 0 || self.src[..offset].ends_with('\n')
+    }
+
+    /// Replacement for the inline value `old`, keeping its quoting style
+    fn inline(&self, old: &str, new: &JsonValue) -> Option<String> {
+        match new {
+            JsonValue::Object(map) if map.is_empty() => Some("{}".to_string()),
+            JsonValue::Array(items) if items.is_empty() => Some("[]".to_string()),
+            JsonValue::Object(_) | JsonValue::Array(_) => {
+                (old.starts_with('[') || old.starts_with('{')).then(|| flow(new))
+            }
+            JsonValue::String(s) if old.starts_with('\'') && !s.contains('\n') => {
+                Some(format!("'{}'", s.replace('\'', "''")))
+            }
+            JsonValue::String(_) if old.starts_with('"') => Some(new.to_string()),
+            _ => Some(scalar(new)),
+        }
+    }
+
+    /// Text after a `key:` or `-` indicator for a new value
+    fn fresh(&self, value: &JsonValue, indent: usize, dash: bool) -> String {
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                if dash {
+                    format!(" {}", self.mapping_lines(map, indent + 2).trim_start())
+                } else {
+                    format!("\n{}", self.mapping_lines(map, indent + self.style.unit))
+                }
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                if dash {
+                    format!(" {}", self.sequence_lines(items, indent + 2).trim_start())
+                } else {
+                    format!(
+                        "\n{}",
+                        self.sequence_lines(items, indent + self.style.sequence)
+                    )
+                }
+            }
+            _ => format!(" {}", scalar(value)),
+        }
+    }
+
+    fn mapping_lines(&self, map: &Map<String, JsonValue>, indent: usize) -> String {
+        let lines: Vec<String> = map
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}{}:{}",
+                    pad(indent),
+                    key_text(key),
+                    self.fresh(value, indent, false)
+                )
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    fn sequence_lines(&self, items: &[JsonValue], indent: usize) -> String {
+        let lines: Vec<String> = items
+            .iter()
+            .map(|value| format!("{}-{}", pad(indent), self.fresh(value, indent, true)))
+            .collect();
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WORKFLOW: &str = "# CI workflow
+name: CI
+on:
+  push:
+    branches: [main]
+
+jobs:
+  test:
+    runs-on: ubuntu-latest  # pinned runner
+    steps:
+      - uses: actions/checkout@v4
+      # build everything
+      - name: Build
+        run: |
+          cargo build
+          cargo test
+";
+
+    #[test]
+    fn test_parse_yaml() {
+        let value = parse(WORKFLOW, "ci.yml").unwrap();
+        assert_eq!(value["on"]["push"]["branches"], json!(["main"]));
+        assert_eq!(value["jobs"]["test"]["steps"][1]["name"], json!("Build"));
+        assert_eq!(
+            value["jobs"]["test"]["steps"][1]["run"],
+            json!("cargo build\ncargo test\n")
+        );
+        assert!(parse("a: [1", "ci.yml")
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid YAML syntax in ci.yml"));
+    }
+
+    #[test]
+    fn test_write_preserves_comments() {
+        let mut value = parse(WORKFLOW, "ci.yml").unwrap();
+        assert_eq!(write(WORKFLOW, &value).unwrap(), WORKFLOW);
+
+        value["jobs"]["test"]["runs-on"] = json!("macos-latest");
+        value["on"]["push"]["branches"] = json!(["main", "next"]);
+        value["jobs"]["test"]["timeout-minutes"] = json!(10);
+        value["jobs"]["test"]["steps"][1]["run"] = json!("cargo test");
+        value["jobs"]["test"]["steps"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"name": "Lint", "run": "cargo clippy"}));
+
+        assert_eq!(
+            write(WORKFLOW, &value).unwrap(),
+            "# CI workflow
+name: CI
+on:
+  push:
+    branches: [main, next]
+
+jobs:
+  test:
+    runs-on: macos-latest  # pinned runner
+    steps:
+      - uses: actions/checkout@v4
+      # build everything
+      - name: Build
+        run: cargo test
+      - name: Lint
+        run: cargo clippy
+    timeout-minutes: 10
+"
+        );
+    }
+
+    #[test]
+    fn test_write_quotes_ambiguous_strings() {
+        let original = "version: '1.0' # keep quotes\nflag: on\n";
+        let mut value = parse(original, "a.yaml").unwrap();
+        value["version"] = json!("2.0");
+        value["flag"] = json!("yes");
+        value["count"] = json!("42");
+
+        assert_eq!(
+            write(original, &value).unwrap(),
+            "version: '2.0' # keep quotes\nflag: \"yes\"\ncount: \"42\"\n"
+        );
+    }
+
+    const COMPOSE: &str = "# services
+x-command: &command [run, --once]
+x-common: &common
+  restart: always
+  logging: {driver: local}
+services:
+  web:
+    <<: *common
+    image: nginx  # front end
+  worker:
+    <<: *common
+    restart: \"no\"
+    command: *command
+";
+
+    #[test]
+    fn test_parse_resolves_merge_keys() {
+        let value = parse(
+            "base: &base {a: 1, b: 2}\nother: {c: 3}\nx:\n  <<: [*base, {a: 0, d: 4}]\n  b: 5\n",
+            "a.yaml",
+        )
+        .unwrap();
+        assert_eq!(value["x"], json!({"a": 1, "d": 4, "b": 5}));
+
+        let value = parse(COMPOSE, "compose.yml").unwrap();
+        assert_eq!(
+            value["services"]["web"],
+            json!({"restart": "always", "logging": {"driver": "local"}, "image": "nginx"})
+        );
+        assert_eq!(value["services"]["worker"]["restart"], json!("no"));
+        assert_eq!(
+            value["services"]["worker"]["command"],
+            json!(["run", "--once"])
+        );
+    }
+
+    #[test]
+    fn test_write_around_anchors_and_aliases() {
+        let mut value = parse(COMPOSE, "compose.yml").unwrap();
+        value["services"]["web"]["image"] = json!("caddy");
+        value["services"]["web"]["ports"] = json!([80]);
+        value["services"]["worker"]["restart"] = json!("always");
+        assert_eq!(
+            write(COMPOSE, &value).unwrap(),
+            COMPOSE
+                .replace("nginx", "caddy")
+                .replace("  worker:", "    ports:\n      - 80\n  worker:")
+                .replace("\"no\"", "\"always\"")
+        );
+
+        for (path, message) in [
+            (
+                "/x-common/restart",
+                "x-common has an anchor (&name), so its content cannot be edited",
+            ),
+            (
+                "/services/web/restart",
+                "services.web.restart comes from a merge key (<<) and cannot be edited",
+            ),
+            (
+                "/services/worker/command/0",
+                "services.worker.command is an alias (*name), so its content cannot be edited",
+            ),
+        ] {
+            let mut value = parse(COMPOSE, "compose.yml").unwrap();
+            *value.pointer_mut(path).unwrap() = json!("edited");
+            let error = write(COMPOSE, &value).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!("Unsupported YAML content: {}", message)
+            );
+            assert_eq!(error.exit_code(), 5);
+        }
+
+        let mut value = parse(COMPOSE, "compose.yml").unwrap();
+        value.as_object_mut().unwrap().remove("x-common");
+        assert!(write(COMPOSE, &value).is_err());
+
+        assert_eq!(
+            write("# empty\n", &json!({"a": 1})).unwrap(),
+            "# empty\na: 1\n"
+        );
+    }
+}
@@ -3,56 +3,141 @@
 use anyhow::{Context, Result};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
-use std::fs;
 
-use super::types::{ExtractConfig, ExtractionResult};
-use super::utils::{format_output, get_nested_value, strip_quotes_internal};
+use super::types::{ExtractConfig, ExtractionResult, Missing};
+use super::utils::{find_nested_value, format_field, get_nested_value};
 use crate::error::JsonExtractError;
+use crate::format::Format;
+use crate::source::{parse_json, read_source, INLINE_SOURCE};
 
-/// Extract a single field from a JSON file
+/// Extract a single field from a JSON file (`-` reads stdin)
 pub fn extract_field(config: &ExtractConfig) -> Result<String> {
-    let content = fs::read_to_string(&config.file_path)
-        .context(format!("Failed to read file: {}", config.file_path))?;
+    let content = read_source(&config.file_path)?;
+    extract_field_from_str(&content, config)
+}
 
-    let value: JsonValue = serde_json::from_str(&content)
-        .context(format!("Invalid JSON syntax in: {}", config.file_path))?;
+/// Extract a single field from JSON text
+///
+/// `config.file_path` names the source in error messages and selects the
+/// format when `config.format` is None.
+pub fn extract_field_from_str(content: &str, config: &ExtractConfig) -> Result<String> {
+    let value =
+        Format::resolve(config.format, &config.file_path).parse(content, &config.file_path)?;
+    field(&value, config)
+}
 
-    let field_value = get_nested_value(&value, &config.field_path)
+/// Format the field selected by `config` from a parsed document
+pub(crate) fn field(value: &JsonValue, config: &ExtractConfig) -> Result<String> {
+    let field_value = get_nested_value(value, &config.field_path)
         .context(format!("Field not found: {}", config.field_path))?;
 
-    let mut result = format_output(field_value, config.output_format.as_deref())?;
+    format_field(
+        field_value,
+        config.output_format.as_deref(),
+        config.strip_quotes,
+    )
+}
+
+/// Extract a single field from a JSON file, or None when the field does not exist
+///
+/// Unreadable files, invalid documents and malformed paths are still errors.
+pub fn extract_optional_field(config: &ExtractConfig) -> Result<Option<String>> {
+    let content = read_source(&config.file_path)?;
+    extract_optional_field_from_str(&content, config)
+}
 
-    if config.strip_quotes {
-        result = strip_quotes_internal(&result);
+/// Extract a single field from JSON text, or None when the field does not exist
+pub fn extract_optional_field_from_str(
+    content: &str,
+    config: &ExtractConfig,
+) -> Result<Option<String>> {
+    let value =
+        Format::resolve(config.format, &config.file_path).parse(content, &config.file_path)?;
+    optional_field(&value, config, Missing::Skip)
+}
+
+/// Format the field selected by `config`, handling a missing field as `missing` says
+///
+/// Returns None only for [`Missing::Skip`].
+pub(crate) fn optional_field(
+    value: &JsonValue,
+    config: &ExtractConfig,
+    missing: Missing,
+) -> Result<Option<String>> {
+    match find_nested_value(value, &config.field_path)? {
+        Some(field_value) => Ok(Some(format_field(
+            field_value,
+            config.output_format.as_deref(),
+            config.strip_quotes,
+        )?)),
+        None => fallback(value, &config.field_path, missing),
     }
+}
 
-    Ok(result)
+/// Value used in place of the missing field `field_path`
+fn fallback(value: &JsonValue, field_path: &str, missing: Missing) -> Result<Option<String>> {
+    match missing {
+        // Report the error of the failed lookup
+        Missing::Error => get_nested_value(value, field_path)
+            .context(format!("Field not found: {}", field_path))
+            .map(|_| None),
+        Missing::Default(text) => Ok(Some(text.to_string())),
+        Missing::Skip => Ok(None),
+    }
+}
+
+/// Read and parse a file in the format detected from its name
+fn load(file_path: &str) -> Result<JsonValue> {
+    let content = read_source(file_path)?;
+    Ok(Format::detect(file_path).parse(&content, file_path)?)
 }
 
-/// Extract multiple fields from a JSON file
+/// Extract multiple fields from a JSON file (`-` reads stdin)
 pub fn extract_multiple_fields(
     file_path: &str,
     field_paths: &[String],
     strip_quotes: bool,
 ) -> Result<ExtractionResult> {
-    let content =
-        fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+    let content = read_source(file_path)?;
+    extract_multiple_fields_from_str(&content, file_path, field_paths, strip_quotes)
+}
 
-    let value: JsonValue =
-        serde_json::from_str(&content).context(format!("Invalid JSON syntax in: {}", file_path))?;
+/// Extract multiple fields from JSON text read from `source_file`
+pub fn extract_multiple_fields_from_str(
+    content: &str,
+    source_file: &str,
+    field_paths: &[String],
+    strip_quotes: bool,
+) -> Result<ExtractionResult> {
+    let value = Format::detect(source_file).parse(content, source_file)?;
+    multiple_fields(
+        &value,
+        source_file,
+        field_paths,
+        strip_quotes,
+        Missing::Error,
+    )
+}
 
-    let mut result = ExtractionResult::new(file_path.to_string());
+/// Collect several fields of a parsed document read from `source_file`
+pub(crate) fn multiple_fields(
+    value: &JsonValue,
+    source_file: &str,
+    field_paths: &[String],
+    strip_quotes: bool,
+    missing: Missing,
+) -> Result<ExtractionResult> {
+    let mut result = ExtractionResult::new(source_file.to_string(), strip_quotes);
 
     for field_path in field_paths {
-        let field_value = get_nested_value(&value, field_path)
-            .context(format!("Field not found: {}", field_path))?;
-
-        let mut formatted_value = format_output(field_value, None)?;
-        if strip_quotes {
-            formatted_value = strip_quotes_internal(&formatted_value);
+        // A default stands in as a string value
+        let field_value = match find_nested_value(value, field_path)? {
+            Some(field_value) => Some(field_value.clone()),
+            None => fallback(value, field_path, missing)?.map(JsonValue::String),
+        };
+        if let Some(field_value) = field_value {
+            result.add_field(field_path.clone(), field_value);
         }
-
-        result.add_field(field_path.clone(), formatted_value);
     }
 
     Ok(result)
@@ -69,20 +154,26 @@ pub fn extract_array(
         field_path: array_path.to_string(),
         output_format: output_format.map(|s| s.to_string()),
         strip_quotes: false,
+        format: None,
     };
     extract_field(&config)
 }
 
-/// Extract array length from a JSON file
+/// Extract array length from a JSON file (`-` reads stdin)
 pub fn extract_array_length(file_path: &str, array_path: &str) -> Result<usize> {
-    let content =
-        fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+    let value = load(file_path)?;
+    array_length(&value, array_path)
+}
 
-    let value: JsonValue =
-        serde_json::from_str(&content).context(format!("Invalid JSON syntax in: {}", file_path))?;
+/// Extract array length from JSON text
+pub fn extract_array_length_from_str(content: &str, array_path: &str) -> Result<usize> {
+    array_length(&parse_json(content, INLINE_SOURCE)?, array_path)
+}
 
+/// Length of the array at `array_path`
+pub(crate) fn array_length(value: &JsonValue, array_path: &str) -> Result<usize> {
     let array_value =
-        get_nested_value(&value, array_path).context(format!("Array not found: {}", array_path))?;
+        get_nested_value(value, array_path).context(format!("Array not found: {}", array_path))?;
 
     let array = array_value
         .as_array()
@@ -91,21 +182,37 @@ pub fn extract_array_length(file_path: &str, array_path: &str) -> Result<usize>
     Ok(array.len())
 }
 
-/// Extract a specific array element from a JSON file
+/// Extract a specific array element from a JSON file (`-` reads stdin)
 pub fn extract_array_element(
     file_path: &str,
     array_path: &str,
     index: usize,
     strip_quotes: bool,
 ) -> Result<String> {
-    let content =
-        fs::read_to_string(file_path).context(format!("Failed to read file: {}", file_path))?;
+    let value = load(file_path)?;
+    array_element(&value, array_path, index, strip_quotes)
+}
 
-    let value: JsonValue =
-        serde_json::from_str(&content).context(format!("Invalid JSON syntax in: {}", file_path))?;
+/// Extract a specific array element from JSON text
+pub fn extract_array_element_from_str(
+    content: &str,
+    array_path: &str,
+    index: usize,
+    strip_quotes: bool,
+) -> Result<String> {
+    let value = parse_json(content, INLINE_SOURCE)?;
+    array_element(&value, array_path, index, strip_quotes)
+}
 
+/// Format the element at `index` of the array at `array_path`
+pub(crate) fn array_element(
+    value: &JsonValue,
+    array_path: &str,
+    index: usize,
+    strip_quotes: bool,
+) -> Result<String> {
     let array_value =
-        get_nested_value(&value, array_path).context(format!("Array not found: {}", array_path))?;
+        get_nested_value(value, array_path).context(format!("Array not found: {}", array_path))?;
 
     let array = array_value
         .as_array()
@@ -120,14 +227,7 @@ pub fn extract_array_element(
         .into());
     }
 
-    let element = &array[index];
-    let mut result = format_output(element, None)?;
-
-    if strip_quotes {
-        result = strip_quotes_internal(&result);
-    }
-
-    Ok(result)
+    format_field(&array[index], None, strip_quotes)
 }
 
 // Preset extraction functions for common JSON structures
@@ -140,6 +240,7 @@ pub fn get_package_name(file_path: Option<&str>) -> Result<String> {
         field_path: "name".to_string(),
         output_format: None,
         strip_quotes: true,
+        format: None,
     };
     extract_field(&config)
 }
@@ -152,6 +253,7 @@ pub fn get_package_version(file_path: Option<&str>) -> Result<String> {
         field_path: "version".to_string(),
         output_format: None,
         strip_quotes: true,
+        format: None,
     };
     extract_field(&config)
 }
@@ -159,10 +261,17 @@ pub fn get_package_version(file_path: Option<&str>) -> Result<String> {
 /// Get all dependencies from a package.json file
 pub fn get_dependencies(file_path: Option<&str>) -> Result<HashMap<String, String>> {
     let path = file_path.unwrap_or("package.json");
-    let content = fs::read_to_string(path).context("Failed to read package.json")?;
+    let value = load(path)?;
+    Ok(dependencies(&value))
+}
 
-    let value: JsonValue = serde_json::from_str(&content).context("Invalid JSON syntax")?;
+/// Get all dependencies from package.json text
+pub fn get_dependencies_from_str(content: &str) -> Result<HashMap<String, String>> {
+    Ok(dependencies(&parse_json(content, INLINE_SOURCE)?))
+}
 
+/// Collect the `dependencies` object as name -> version range
+pub(crate) fn dependencies(value: &JsonValue) -> HashMap<String, String> {
     let mut dependencies = HashMap::new();
 
     if let Some(deps) = value.get("dependencies") {
@@ -173,12 +282,13 @@ pub fn get_dependencies(file_path: Option<&str>) -> Result<HashMap<String, Strin
         }
     }
 
-    Ok(dependencies)
+    dependencies
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::exit_code;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -204,4 +314,131 @@ mod tests {
 
         assert_eq!(extract_array_length(path, "authors").unwrap(), 2);
     }
+
+    #[test]
+    fn test_extract_from_str() {
+        let content = r#"{"name": "test", "authors": ["Alice"]}"#;
+        let config = ExtractConfig {
+            file_path: "-".to_string(),
+            field_path: "name".to_string(),
+            strip_quotes: true,
+            ..Default::default()
+        };
+
+        assert_eq!(extract_field_from_str(content, &config).unwrap(), "test");
+        assert_eq!(
+            extract_array_length_from_str(content, "authors").unwrap(),
+            1
+        );
+        assert!(extract_field_from_str("{", &config).is_err());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let config = ExtractConfig {
+            file_path: "-".to_string(),
+            field_path: "missing".to_string(),
+            ..Default::default()
+        };
+
+        let missing = extract_field_from_str(r#"{"name": "test"}"#, &config).unwrap_err();
+        assert_eq!(exit_code(&missing), 4);
+        let invalid = extract_field_from_str("{", &config).unwrap_err();
+        assert_eq!(exit_code(&invalid), 5);
+        let json5 = ExtractConfig {
+            file_path: "x.json5".to_string(),
+            field_path: "max".to_string(),
+            ..Default::default()
+        };
+        let infinity = extract_field_from_str("{hex: 0x10, max: Infinity}", &json5).unwrap_err();
+        assert_eq!(exit_code(&infinity), 5);
+        let hex = ExtractConfig {
+            field_path: "hex".to_string(),
+            ..json5
+        };
+        assert_eq!(
+            extract_field_from_str("{hex: 0x10, max: Infinity}", &hex).unwrap(),
+            "16"
+        );
+        let unreadable = extract_field(&ExtractConfig {
+            file_path: "/nonexistent/package.json".to_string(),
+            ..config
+        })
+        .unwrap_err();
+        assert_eq!(exit_code(&unreadable), 6);
+    }
+
+    #[test]
+    fn test_extract_optional_field() {
+        let content = r#"{"name": "test", "publishConfig": {}, "files": []}"#;
+        let config = |field_path: &str| ExtractConfig {
+            file_path: "package.json".to_string(),
+            field_path: field_path.to_string(),
+            strip_quotes: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            extract_optional_field_from_str(content, &config("name")).unwrap(),
+            Some("test".to_string())
+        );
+        assert_eq!(
+            extract_optional_field_from_str(content, &config("publishConfig.registry")).unwrap(),
+            None
+        );
+        assert_eq!(
+            extract_optional_field_from_str(content, &config("files[0]")).unwrap(),
+            None
+        );
+        assert!(extract_optional_field_from_str(content, &config("files[x]")).is_err());
+
+        let value: JsonValue = serde_json::from_str(content).unwrap();
+        let paths = ["name".to_string(), "license".to_string()];
+        let result = multiple_fields(
+            &value,
+            "package.json",
+            &paths,
+            true,
+            Missing::Default("MIT"),
+        )
+        .unwrap();
+        assert_eq!(result.values().collect::<Vec<_>>(), ["test", "MIT"]);
+        let result = multiple_fields(&value, "package.json", &paths, true, Missing::Skip).unwrap();
+        assert_eq!(result.values().collect::<Vec<_>>(), ["test"]);
+    }
+
+    #[test]
+    fn test_extract_multiple_fields_keeps_request_order() {
+        let content = r#"{"name": "demo", "version": "1.0.0"}"#;
+        let paths: Vec<String> = ["version", "name", "version"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let result =
+            extract_multiple_fields_from_str(content, "package.json", &paths, true).unwrap();
+
+        assert_eq!(
+            result.values().collect::<Vec<_>>(),
+            ["1.0.0", "demo", "1.0.0"]
+        );
+        assert_eq!(
+            result.to_json().unwrap(),
+            r#"{"version":"1.0.0","name":"demo"}"#
+        );
+        assert_eq!(result.repeated_field(), Some("version"));
+
+        // Without -r the lines keep JSON quotes, but the JSON output and
+        // --values-only are not double-encoded
+        let content = r#"{"name": "demo", "version": "1.0.0", "private": true}"#;
+        let paths = ["version".to_string(), "private".to_string()];
+        let result =
+            extract_multiple_fields_from_str(content, "package.json", &paths, false).unwrap();
+        assert_eq!(result.values().collect::<Vec<_>>(), ["\"1.0.0\"", "true"]);
+        assert_eq!(result.raw_values().collect::<Vec<_>>(), ["1.0.0", "true"]);
+        assert_eq!(result.repeated_field(), None);
+        assert_eq!(
+            result.to_json().unwrap(),
+            r#"{"version":"1.0.0","private":true}"#
+        );
+    }
 }
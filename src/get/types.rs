@@ -1,6 +1,8 @@
 //! Type definitions for get command
 
-use std::collections::HashMap;
+use serde_json::Value as JsonValue;
+
+use crate::format::Format;
 
 /// Configuration for field extraction
 #[derive(Debug, Clone)]
@@ -9,10 +11,12 @@ pub struct ExtractConfig {
     pub file_path: String,
     /// Dot-separated path to the field (e.g., "package.name", "dependencies.serde", "authors\[0\]")
     pub field_path: String,
-    /// Output format (None for raw value, Some("json") for JSON)
+    /// Output format (None for raw value; see [`OUTPUT_FORMATS`](super::utils::OUTPUT_FORMATS))
     pub output_format: Option<String>,
-    /// Whether to strip quotes from string values
+    /// Whether to print string values raw (decoded, without quotes)
     pub strip_quotes: bool,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
 }
 
 impl Default for ExtractConfig {
@@ -22,36 +26,67 @@ impl Default for ExtractConfig {
             field_path: "name".to_string(),
             output_format: None,
             strip_quotes: false,
+            format: None,
         }
     }
 }
 
+/// What a lookup does with a requested field that does not exist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Missing<'a> {
+    /// Fail with a field-not-found error
+    #[default]
+    Error,
+    /// Use this text, printed as given, as the value
+    Default(&'a str),
+    /// Leave the field out of the output
+    Skip,
+}
+
 /// Result of multiple field extraction
+///
+/// Fields keep the order in which they were requested; a field requested
+/// twice appears twice.
 #[derive(Debug, Clone)]
 pub struct ExtractionResult {
-    /// Extracted fields and their values
-    pub fields: HashMap<String, String>,
+    /// Extracted fields and their values, in request order
+    pub fields: Vec<(String, JsonValue)>,
     /// Path to the source JSON file
     pub source_file: String,
+    /// Whether [`values`](Self::values) prints strings raw
+    pub strip_quotes: bool,
 }
 
 impl ExtractionResult {
     /// Create a new extraction result
-    pub fn new(source_file: String) -> Self {
+    pub fn new(source_file: String, strip_quotes: bool) -> Self {
         Self {
-            fields: HashMap::new(),
+            fields: Vec::new(),
             source_file,
+            strip_quotes,
         }
     }
 
     /// Add a field to the result
-    pub fn add_field(&mut self, field_path: String, value: String) {
-        self.fields.insert(field_path, value);
+    pub fn add_field(&mut self, field_path: String, value: JsonValue) {
+        self.fields.push((field_path, value));
     }
 
-    /// Get a field value
-    pub fn get(&self, field_path: &str) -> Option<&String> {
-        self.fields.get(field_path)
+    /// Get the value of the first field with this path
+    pub fn get(&self, field_path: &str) -> Option<&JsonValue> {
+        self.fields
+            .iter()
+            .find(|(path, _)| path == field_path)
+            .map(|(_, value)| value)
+    }
+
+    /// First field path requested more than once, which JSON output cannot key twice
+    pub fn repeated_field(&self) -> Option<&str> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(i, (path, _))| self.fields[..*i].iter().any(|(other, _)| other == path))
+            .map(|(_, (path, _))| path.as_str())
     }
 
     /// Check if result is empty
@@ -59,13 +94,41 @@ impl ExtractionResult {
         self.fields.is_empty()
     }
 
+    /// Values as printed text, in request order
+    ///
+    /// Strings are quoted JSON unless `strip_quotes` is set.
+    pub fn values(&self) -> impl Iterator<Item = String> + '_ {
+        self.fields
+            .iter()
+            .map(|(_, value)| text(value, self.strip_quotes))
+    }
+
+    /// Values in request order with strings always raw, for line-based reads
+    pub fn raw_values(&self) -> impl Iterator<Item = String> + '_ {
+        self.fields.iter().map(|(_, value)| text(value, true))
+    }
+
     /// Convert to JSON string
+    ///
+    /// Keys appear in request order; a repeated field keeps its first position.
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string(&self.fields)
+        serde_json::to_string(&self.to_map())
     }
 
     /// Convert to pretty JSON string
     pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(&self.fields)
+        serde_json::to_string_pretty(&self.to_map())
+    }
+
+    fn to_map(&self) -> serde_json::Map<String, JsonValue> {
+        self.fields.iter().cloned().collect()
+    }
+}
+
+/// Compact text of a value, with strings raw or as quoted JSON
+fn text(value: &JsonValue, raw: bool) -> String {
+    match value {
+        JsonValue::String(text) if raw => text.clone(),
+        other => other.to_string(),
     }
 }
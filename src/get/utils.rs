@@ -1,64 +1,343 @@
 //! Utility functions for get command
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 
 use crate::error::JsonExtractError;
+use crate::format::{json5, Format};
+use crate::path::{child_path, format_path, index_path, parse_path, Segment};
 
 /// Resolve nested value from JSON structure using dot-separated path with array support
+///
+/// See [`crate::path`] for the path syntax. Fails when the value holds a
+/// JSON5 `Infinity` or `NaN`, which have no JSON value.
 pub fn get_nested_value<'a>(
     value: &'a JsonValue,
     path: &str,
 ) -> Result<&'a JsonValue, JsonExtractError> {
+    let segments = parse_path(path)?;
     let mut current = value;
 
-    for part in path.split('.') {
-        // Handle array access syntax [index]
-        if part.contains('[') && part.ends_with(']') {
-            let bracket_start = part.find('[').ok_or_else(|| {
-                JsonExtractError::InvalidArrayIndex(format!("Invalid array syntax: {}", part))
-            })?;
-            let array_name = &part[..bracket_start];
-            let index_str = &part[bracket_start + 1..part.len() - 1];
-            let index = index_str.parse::<usize>().map_err(|_| {
-                JsonExtractError::InvalidArrayIndex(format!("Invalid array index: {}", index_str))
-            })?;
-
-            // Get array from current value
-            current = current
-                .get(array_name)
-                .ok_or_else(|| JsonExtractError::FieldNotFound(array_name.to_string()))?;
-            let array = current
-                .as_array()
-                .ok_or_else(|| JsonExtractError::NotAnArray(array_name.to_string()))?;
-
-            // Check index bounds
-            if index >= array.len() {
-                return Err(JsonExtractError::ArrayIndexOutOfBounds {
-                    path: array_name.to_string(),
-                    index,
-                    length: array.len(),
-                });
+    for (i, segment) in segments.iter().enumerate() {
+        current = match segment {
+            Segment::Key(key) => current
+                .get(key)
+                .ok_or_else(|| JsonExtractError::FieldNotFound(key.clone()))?,
+            Segment::Index(index) => {
+                let array = current
+                    .as_array()
+                    .ok_or_else(|| JsonExtractError::NotAnArray(format_path(&segments[..i])))?;
+                array
+                    .get(*index)
+                    .ok_or_else(|| JsonExtractError::ArrayIndexOutOfBounds {
+                        path: format_path(&segments[..i]),
+                        index: *index,
+                        length: array.len(),
+                    })?
             }
-
-            current = &array[index];
-        } else {
-            // Regular field access
-            current = current
-                .get(part)
-                .ok_or_else(|| JsonExtractError::FieldNotFound(part.to_string()))?;
-        }
+        };
     }
 
+    json5::check_finite(current)?;
     Ok(current)
 }
 
+/// Resolve a nested value like [`get_nested_value`], with None for a path that does not exist
+///
+/// Only missing fields and out-of-range indexes count as absent; a malformed
+/// path or indexing into a non-array is still an error.
+pub fn find_nested_value<'a>(
+    value: &'a JsonValue,
+    path: &str,
+) -> Result<Option<&'a JsonValue>, JsonExtractError> {
+    match get_nested_value(value, path) {
+        Ok(value) => Ok(Some(value)),
+        Err(
+            JsonExtractError::FieldNotFound(_) | JsonExtractError::ArrayIndexOutOfBounds { .. },
+        ) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Name of the JSON type of a value (object, array, string, number, boolean, null)
+pub fn value_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Object(_) => "object",
+        JsonValue::Array(_) => "array",
+        JsonValue::String(_) => "string",
+        JsonValue::Number(_) => "number",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Null => "null",
+    }
+}
+
+/// Output formats accepted by [`format_output`]
+pub const OUTPUT_FORMATS: &[&str] = &[
+    "raw",
+    "json",
+    "json-pretty",
+    "yaml",
+    "toml",
+    "csv",
+    "tsv",
+    "env",
+    "shell",
+];
+
 /// Format JSON value for output based on specified format
+///
+/// `csv` and `tsv` expect an array of objects (or a single object) and print a
+/// header row with every key seen; `env` and `shell` expect an object or array
+/// and print one variable per leaf, named after its upper-cased path.
 pub fn format_output(value: &JsonValue, output_format: Option<&str>) -> Result<String> {
     match output_format {
+        Some("raw") | Some("json") | None => Ok(serde_json::to_string(value)?),
         Some("json-pretty") => Ok(serde_json::to_string_pretty(value)?),
-        Some("raw") | None => Ok(value.to_string()),
-        _ => Ok(serde_json::to_string(value)?),
+        Some("yaml") => Ok(Format::Yaml.emit(value)?.trim_end().to_string()),
+        Some("toml") => Ok(Format::Toml.emit(value)?.trim_end().to_string()),
+        Some("csv") => table_output(value, ','),
+        Some("tsv") => table_output(value, '\t'),
+        Some("env") => Ok(env_variables(value)?
+            .iter()
+            .map(|(name, text)| format!("{}={}", name, dotenv_quote(text)))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        Some("shell") => Ok(env_variables(value)?
+            .iter()
+            .map(|(name, text)| format!("export {}={}", name, shell_quote(text)))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        Some(other) => Err(anyhow!(
+            "Unknown output format: {} (expected one of: {})",
+            other,
+            OUTPUT_FORMATS.join(", ")
+        )),
+    }
+}
+
+/// Format a field value, optionally printing strings raw
+///
+/// With `raw_strings` and the default output format, strings are printed with
+/// their escapes decoded and without quotes, like `jq -r`; other values stay
+/// JSON. Other output formats are printed as they are.
+pub fn format_field(
+    value: &JsonValue,
+    output_format: Option<&str>,
+    raw_strings: bool,
+) -> Result<String> {
+    match (value, output_format) {
+        (JsonValue::String(text), None | Some("raw")) if raw_strings => Ok(text.clone()),
+        _ => format_output(value, output_format),
+    }
+}
+
+/// Text of a value in plain-text output: strings without quotes, null as
+/// nothing, everything else as JSON
+pub fn plain_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Rows of an array of objects with a header row, separated by `delimiter`
+fn table_output(value: &JsonValue, delimiter: char) -> Result<String> {
+    let rows: Vec<&serde_json::Map<String, JsonValue>> = match value {
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_object().ok_or_else(|| {
+                    JsonExtractError::InvalidValueType(format!(
+                        "table output needs an array of objects, found an array containing {}",
+                        value_type_name(item)
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        JsonValue::Object(map) => vec![map],
+        other => {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "table output needs an array of objects, not {}",
+                value_type_name(other)
+            ))
+            .into())
+        }
+    };
+
+    let mut columns: Vec<&String> = Vec::new();
+    for key in rows.iter().flat_map(|row| row.keys()) {
+        if !columns.contains(&key) {
+            columns.push(key);
+        }
+    }
+
+    let cell = |text: &str| {
+        if delimiter == '\t' {
+            text.replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        } else if text.contains([delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    };
+    let separator = delimiter.to_string();
+    let mut lines = vec![columns
+        .iter()
+        .map(|column| cell(column))
+        .collect::<Vec<_>>()
+        .join(&separator)];
+    for row in rows {
+        lines.push(
+            columns
+                .iter()
+                .map(|column| cell(&row.get(*column).map(plain_text).unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(&separator),
+        );
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Flatten an object or array into `(NAME, text)` pairs, one per leaf
+///
+/// Names join the path segments with `_`, upper-cased, with every character
+/// that is not valid in a variable name replaced by `_`. Fails when two
+/// leaves end up with the same name.
+pub fn env_variables(value: &JsonValue) -> Result<Vec<(String, String)>, JsonExtractError> {
+    if !value.is_object() && !value.is_array() {
+        return Err(JsonExtractError::InvalidValueType(format!(
+            "variable output needs an object or array, not {}",
+            value_type_name(value)
+        )));
+    }
+    unique_names(
+        env_leaves(value, "")
+            .into_iter()
+            .map(|(name, path, text)| (env_name(&name), path, text)),
+    )
+}
+
+/// `(NAME, text)` pairs of `(NAME, path, text)` leaves, failing when two
+/// paths share a name
+pub fn unique_names(
+    leaves: impl IntoIterator<Item = (String, String, String)>,
+) -> Result<Vec<(String, String)>, JsonExtractError> {
+    let mut paths: HashMap<String, String> = HashMap::new();
+    let mut pairs = Vec::new();
+    for (name, path, text) in leaves {
+        if let Some(first) = paths.get(&name) {
+            return Err(JsonExtractError::NameCollision {
+                first: first.clone(),
+                second: path,
+                name,
+            });
+        }
+        paths.insert(name.clone(), path);
+        pairs.push((name, text));
+    }
+    Ok(pairs)
+}
+
+/// Every leaf of `value` as its path segments joined with `_`, its field path
+/// below `path` (the path of `value`) and its plain text
+///
+/// Empty objects and arrays are leaves; a scalar is a single leaf with an
+/// empty name.
+pub fn env_leaves(value: &JsonValue, path: &str) -> Vec<(String, String, String)> {
+    fn visit(value: &JsonValue, name: &str, path: &str, out: &mut Vec<(String, String, String)>) {
+        let child = |segment: &str| {
+            if name.is_empty() {
+                segment.to_string()
+            } else {
+                format!("{}_{}", name, segment)
+            }
+        };
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    visit(value, &child(key), &child_path(path, key), out);
+                }
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                for (index, item) in items.iter().enumerate() {
+                    visit(
+                        item,
+                        &child(&index.to_string()),
+                        &index_path(path, index),
+                        out,
+                    );
+                }
+            }
+            leaf => out.push((name.to_string(), path.to_string(), plain_text(leaf))),
+        }
+    }
+
+    let mut leaves = Vec::new();
+    visit(value, "", path, &mut leaves);
+    leaves
+}
+
+/// Environment variable name for a flattened path
+pub fn env_name(path: &str) -> String {
+    variable_name(path).to_ascii_uppercase()
+}
+
+/// Variable name for a flattened path, keeping the case of its letters
+///
+/// Characters that are not ASCII letters or digits become `_`, and a leading
+/// digit is prefixed with `_`.
+pub fn variable_name(path: &str) -> String {
+    let name: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// Quote a value for a POSIX shell (single quotes, `'` written as `'\''`)
+pub fn shell_quote(text: &str) -> String {
+    if !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+,=".contains(c))
+    {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Quote a value for a `.env` file (double quotes with backslash escapes)
+pub fn dotenv_quote(text: &str) -> String {
+    if text
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+,".contains(c))
+    {
+        text.to_string()
+    } else {
+        let mut quoted = String::from("\"");
+        for c in text.chars() {
+            match c {
+                '\\' | '"' | '$' | '`' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
     }
 }
 
@@ -109,6 +388,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_output_tables_and_variables() {
+        let rows = json!([{"name": "a, b", "port": 80}, {"name": "c", "tls": true}]);
+        assert_eq!(
+            format_output(&rows, Some("csv")).unwrap(),
+            "name,port,tls\n\"a, b\",80,\nc,,true"
+        );
+        assert_eq!(
+            format_output(&rows, Some("tsv")).unwrap(),
+            "name\tport\ttls\na, b\t80\t\nc\t\ttrue"
+        );
+
+        let config = json!({"db": {"host": "localhost", "pass": "it's $x"}, "ports": [80]});
+        assert_eq!(
+            format_output(&config, Some("env")).unwrap(),
+            "DB_HOST=localhost\nDB_PASS=\"it's \\$x\"\nPORTS_0=80"
+        );
+        assert_eq!(
+            format_output(&config, Some("shell")).unwrap(),
+            "export DB_HOST=localhost\nexport DB_PASS='it'\\''s $x'\nexport PORTS_0=80"
+        );
+
+        assert!(format_output(&json!("x"), Some("env")).is_err());
+        let error = format_output(&json!({"a": {"b": 1}, "a_b": 2}), Some("shell")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Variable name collision: a.b and a_b both map to A_B"
+        );
+        assert_eq!(crate::error::exit_code(&error), 7);
+        assert!(format_output(&rows, Some("xml"))
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown output format: xml"));
+    }
+
+    #[test]
+    fn test_format_field_raw_strings() {
+        let value = json!("line 1\n\"quoted\" caf\u{e9}");
+        assert_eq!(
+            format_field(&value, None, true).unwrap(),
+            "line 1\n\"quoted\" café"
+        );
+        assert_eq!(format_field(&json!("'x'"), None, true).unwrap(), "'x'");
+        assert_eq!(format_field(&json!([1]), None, true).unwrap(), "[1]");
+        assert_eq!(
+            format_field(&value, None, false).unwrap(),
+            value.to_string()
+        );
+        assert_eq!(
+            format_field(&json!("\"x\""), Some("yaml"), true).unwrap(),
+            r#""\"x\"""#
+        );
+    }
+
     #[test]
     fn test_strip_quotes_internal() {
         assert_eq!(strip_quotes_internal("\"hello\""), "hello");
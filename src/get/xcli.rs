@@ -1,10 +1,19 @@
 //! CLI definitions and handling for get command
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use clap::{Arg, Command};
+use serde_json::Value as JsonValue;
+use std::io::{self, BufWriter, Write};
 
 use super::core::*;
-use super::types::ExtractConfig;
+use super::types::{ExtractConfig, Missing};
+use super::utils::OUTPUT_FORMATS;
+use crate::batch::{expand_file_patterns, is_glob_pattern, process_files};
+use crate::document;
+use crate::error::{exit_code, ExitStatus, JsonExtractError};
+use crate::format::xcli::{format_arg, selected_format};
+use crate::ndjson;
+use crate::source::read_source;
 
 /// Define the get command CLI structure
 pub fn get_command() -> Command {
@@ -15,7 +24,8 @@ pub fn get_command() -> Command {
                 .short('f')
                 .long("file")
                 .value_name("FILE")
-                .help("JSON file path")
+                .help("JSON file path or glob pattern ('-' for stdin, can be used multiple times)")
+                .action(clap::ArgAction::Append)
                 .default_value("package.json"),
         )
         .arg(
@@ -42,18 +52,49 @@ pub fn get_command() -> Command {
                 .action(clap::ArgAction::Append)
                 .help("Extract multiple fields (can be used multiple times)"),
         )
+        .arg(
+            Arg::new("values-only")
+                .long("values-only")
+                .help("With --multiple, print only the values, one per line in request order (strings are raw)")
+                .requires("multiple")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("default")
+                .long("default")
+                .value_name("VALUE")
+                .help("Print VALUE (as given) instead of failing when a --field or --multiple path does not exist"),
+        )
+        .arg(
+            Arg::new("optional")
+                .long("optional")
+                .help("Print nothing and exit 0 when a --field or --multiple path does not exist")
+                .conflicts_with("default")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("output")
                 .short('o')
                 .long("output")
                 .value_name("FORMAT")
-                .help("Output format (raw, json, json-pretty)")
+                .help("Output format (csv/tsv need an array of objects; env/shell print flattened variables)")
+                .value_parser(OUTPUT_FORMATS.to_vec())
                 .default_value("raw"),
         )
         .arg(
             Arg::new("strip-quotes")
-                .long("strip-quotes")
-                .help("Strip surrounding quotes from string values")
+                .short('r')
+                .long("raw-output")
+                .visible_alias("strip-quotes")
+                .help("Print strings without quotes and with escapes decoded (like jq -r; only with the default raw output)")
+                .conflicts_with("output")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("print0")
+                .short('0')
+                .long("print0")
+                .help("End each result with a NUL byte instead of a newline, for xargs -0 (implies --raw-output; no file name prefixes; values containing NUL are an error)")
                 .action(clap::ArgAction::SetTrue),
         )
         .arg(
@@ -99,122 +140,275 @@ pub fn get_command() -> Command {
                 .value_name("INDEX")
                 .help("Array index for --array-element (0-based)"),
         )
+        .arg(format_arg())
+        .arg(
+            Arg::new("jsonc")
+                .long("jsonc")
+                .help("Parse input as JSONC (comments, trailing commas); auto-detected for tsconfig.json etc.")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("format"),
+        )
+        .arg(
+            Arg::new("ndjson")
+                .long("ndjson")
+                .help("Treat input as JSON Lines and print one result per record")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["format", "jsonc"]),
+        )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .help("Process multiple files with N worker threads (0 = number of CPUs)")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
         .arg(
             Arg::new("quiet")
                 .short('q')
                 .long("quiet")
-                .help("Suppress error messages for missing fields")
+                .help("Suppress error messages (the exit status still reports failures)")
                 .action(clap::ArgAction::SetTrue),
         )
 }
 
 /// Handle get command logic
 pub fn handle_get_command(matches: &clap::ArgMatches) -> Result<()> {
-    let file_path = matches.get_one::<String>("file").unwrap();
+    let patterns: Vec<String> = matches
+        .get_many::<String>("file")
+        .unwrap()
+        .cloned()
+        .collect();
     let output_format = matches.get_one::<String>("output").unwrap();
-    let strip_quotes = matches.get_flag("strip-quotes");
+    let print0 = matches.get_flag("print0");
+    let strip_quotes = matches.get_flag("strip-quotes") || print0;
+    let end = if print0 { '\0' } else { '\n' };
     let quiet = matches.get_flag("quiet");
+    let missing = match matches.get_one::<String>("default") {
+        Some(text) => Missing::Default(text),
+        None if matches.get_flag("optional") => Missing::Skip,
+        None => Missing::Error,
+    };
+    let format = selected_format(matches)?;
+
+    // Produce the text printed for one document read from `file_path`, if any
+    let extract_value = |file_path: &str, value: &JsonValue| -> Result<Option<String>> {
+        let config =
+            |field_path: &str, output_format: Option<&str>, strip_quotes: bool| ExtractConfig {
+                file_path: file_path.to_string(),
+                field_path: field_path.to_string(),
+                output_format: output_format.map(|s| s.to_string()),
+                strip_quotes,
+                format,
+            };
 
-    // Handle array operations
-    if let Some(array_path) = matches.get_one::<String>("array") {
-        match extract_array(file_path, array_path, Some(output_format)) {
-            Ok(result) => println!("{}", result),
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
+        // Handle array operations
+        if let Some(array_path) = matches.get_one::<String>("array") {
+            return field(value, &config(array_path, Some(output_format), false)).map(Some);
         }
-        return Ok(());
-    }
 
-    if let Some(array_path) = matches.get_one::<String>("array-length") {
-        match extract_array_length(file_path, array_path) {
-            Ok(length) => println!("{}", length),
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
+        if let Some(array_path) = matches.get_one::<String>("array-length") {
+            return Ok(Some(array_length(value, array_path)?.to_string()));
         }
-        return Ok(());
-    }
 
-    if let Some(array_path) = matches.get_one::<String>("array-element") {
-        if let Some(index_str) = matches.get_one::<String>("array-index") {
-            match index_str.parse::<usize>() {
-                Ok(index) => {
-                    match extract_array_element(file_path, array_path, index, strip_quotes) {
-                        Ok(element) => println!("{}", element),
-                        Err(e) if !quiet => eprintln!("Error: {}", e),
-                        _ => {}
-                    }
-                }
-                Err(_) if !quiet => eprintln!("Error: Invalid array index: {}", index_str),
-                _ => {}
+        if let Some(array_path) = matches.get_one::<String>("array-element") {
+            let index_str = matches.get_one::<String>("array-index").unwrap();
+            let index = index_str
+                .parse::<usize>()
+                .map_err(|_| anyhow!("Invalid array index: {}", index_str))?;
+            return array_element(value, array_path, index, strip_quotes).map(Some);
+        }
+
+        // Handle convenience flags
+        if matches.get_flag("package-name") {
+            return field(value, &config("name", None, true)).map(Some);
+        }
+
+        if matches.get_flag("package-version") {
+            return field(value, &config("version", None, true)).map(Some);
+        }
+
+        if matches.get_flag("dependencies") {
+            return Ok(Some(serde_json::to_string_pretty(&dependencies(value))?));
+        }
+
+        // Handle multiple fields extraction
+        if let Some(field_paths) = matches.get_many::<String>("multiple") {
+            let field_paths: Vec<String> = field_paths.cloned().collect();
+            let result = multiple_fields(value, file_path, &field_paths, strip_quotes, missing)?;
+            let values_only = matches.get_flag("values-only");
+            if let Some(path) = result
+                .repeated_field()
+                .filter(|_| !values_only && output_format.starts_with("json"))
+            {
+                return Err(JsonExtractError::InvalidFieldPath(format!(
+                    "{} is requested more than once, which JSON output cannot represent",
+                    path
+                ))
+                .into());
             }
+            let text = if values_only {
+                Ok(result
+                    .raw_values()
+                    .collect::<Vec<_>>()
+                    .join(&end.to_string()))
+            } else if output_format == "json" {
+                Ok(result.to_json()?)
+            } else if output_format == "json-pretty" {
+                Ok(result.to_json_pretty()?)
+            } else {
+                Ok(result
+                    .fields
+                    .iter()
+                    .zip(result.values())
+                    .map(|((field_path, _), value)| format!("{}: {}", field_path, value))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            };
+            // Print nothing when --optional skipped every field
+            return text.map(|text| (!result.is_empty()).then_some(text));
         }
-        return Ok(());
-    }
 
-    // Handle convenience flags
-    if matches.get_flag("package-name") {
-        match get_package_name(Some(file_path)) {
-            Ok(name) => println!("{}", name),
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
+        // Single field extraction
+        let field_path = matches.get_one::<String>("field").unwrap();
+        optional_field(
+            value,
+            &config(field_path, Some(output_format), strip_quotes),
+            missing,
+        )
+    };
+
+    // A NUL inside a value would split its --print0 record in two
+    let extract_value = |file_path: &str, value: &JsonValue| -> Result<Option<String>> {
+        let text = extract_value(file_path, value)?;
+        if print0 && text.as_ref().is_some_and(|text| text.contains('\0')) {
+            return Err(anyhow::Error::new(ExitStatus(2))
+                .context("value contains a NUL byte, which --print0 cannot separate"));
         }
-        return Ok(());
-    }
+        Ok(text)
+    };
 
-    if matches.get_flag("package-version") {
-        match get_package_version(Some(file_path)) {
-            Ok(version) => println!("{}", version),
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
+    // Produce the text printed for one file
+    let extract = |file_path: &str| -> Result<Option<String>> {
+        let content = read_source(file_path)?;
+        let value = document::parse(&content, format, file_path)?;
+        extract_value(file_path, &value)
+    };
+
+    // Errors are printed here (unless --quiet) and only set the exit status,
+    // which follows the first failure
+    let mut failure = None;
+    let mut report = |e: &anyhow::Error, prefix: &str| {
+        if !quiet {
+            eprintln!("Error: {}{:#}", prefix, e);
         }
-        return Ok(());
-    }
+        failure.get_or_insert(exit_code(e));
+    };
+
+    let files = match expand_file_patterns(&patterns) {
+        Ok(files) => files,
+        Err(e) => {
+            report(&e.into(), "");
+            return finish(failure);
+        }
+    };
 
-    if matches.get_flag("dependencies") {
-        match get_dependencies(Some(file_path)) {
-            Ok(deps) => {
-                let json = serde_json::to_string_pretty(&deps)?;
-                println!("{}", json);
+    let prefixed = files.len() > 1 || patterns.iter().any(|p| is_glob_pattern(p));
+
+    // JSON Lines: print one result per record as the input is read
+    if matches.get_flag("ndjson") {
+        let mut out = BufWriter::new(io::stdout().lock());
+        for file in &files {
+            let reader = match ndjson::open_reader(file) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    report(&e, "");
+                    continue;
+                }
+            };
+            for record in ndjson::records(reader) {
+                let record = record.with_context(|| format!("Failed to read {}", file))?;
+                let result = match record.parse(file) {
+                    Ok(value) => extract_value(file, &value).map_err(|e| {
+                        let message = format!("{}: {}", record.location(file), e);
+                        e.context(message)
+                    }),
+                    Err(e) => Err(e.into()),
+                };
+                match result {
+                    Ok(None) => {}
+                    Ok(Some(text)) if prefixed && !print0 => {
+                        for line in text.split('\n') {
+                            writeln!(out, "{}: {}", file, line)?;
+                        }
+                    }
+                    Ok(Some(text)) => write!(out, "{}{}", text, end)?,
+                    Err(e) => {
+                        out.flush()?;
+                        report(&e, "");
+                    }
+                }
             }
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
         }
-        return Ok(());
+        out.flush()?;
+        return finish(failure);
     }
 
-    // Handle multiple fields extraction
-    if let Some(field_paths) = matches.get_many::<String>("multiple") {
-        let field_paths: Vec<String> = field_paths.cloned().collect();
-        match extract_multiple_fields(file_path, &field_paths, strip_quotes) {
-            Ok(result) => {
-                if output_format == "json" {
-                    println!("{}", result.to_json()?);
-                } else if output_format == "json-pretty" {
-                    println!("{}", result.to_json_pretty()?);
-                } else {
-                    for (field_path, value) in result.fields {
-                        println!("{}: {}", field_path, value);
-                    }
+    // A single plain path keeps the unprefixed output
+    if !prefixed {
+        match extract(&files[0]) {
+            Ok(Some(result)) => print!("{}{}", result, end),
+            Ok(None) => {}
+            Err(e) => report(&e, ""),
+        }
+        return finish(failure);
+    }
+
+    let jobs = *matches.get_one::<usize>("jobs").unwrap();
+    let outcomes = process_files(&files, jobs, extract);
+    if output_format == "json" || output_format == "json-pretty" {
+        // Collect values into a {file: value} object
+        let mut object = serde_json::Map::new();
+        for outcome in outcomes {
+            match outcome.result {
+                Ok(None) => {}
+                Ok(Some(text)) => {
+                    let value = serde_json::from_str(&text).unwrap_or(JsonValue::String(text));
+                    object.insert(outcome.file, value);
                 }
+                Err(e) => report(&e, &format!("{}: ", outcome.file)),
             }
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
         }
-    } else if let Some(field_path) = matches.get_one::<String>("field") {
-        // Single field extraction
-        let config = ExtractConfig {
-            file_path: file_path.to_string(),
-            field_path: field_path.to_string(),
-            output_format: Some(output_format.to_string()),
-            strip_quotes,
-        };
-
-        match extract_field(&config) {
-            Ok(result) => println!("{}", result),
-            Err(e) if !quiet => eprintln!("Error: {}", e),
-            _ => {}
+        let object = JsonValue::Object(object);
+        if output_format == "json-pretty" {
+            print!("{}{}", serde_json::to_string_pretty(&object)?, end);
+        } else {
+            print!("{}{}", object, end);
+        }
+    } else {
+        // Prefix every output line with the file name
+        for outcome in outcomes {
+            match outcome.result {
+                Ok(None) => {}
+                Ok(Some(text)) if print0 => print!("{}{}", text, end),
+                Ok(Some(text)) => {
+                    for line in text.lines() {
+                        println!("{}: {}", outcome.file, line);
+                    }
+                }
+                Err(e) => report(&e, &format!("{}: ", outcome.file)),
+            }
         }
     }
 
-    Ok(())
+    finish(failure)
+}
+
+/// Exit status of a command whose failures were already reported
+fn finish(failure: Option<i32>) -> Result<()> {
+    match failure {
+        Some(code) => Err(ExitStatus(code).into()),
+        None => Ok(()),
+    }
 }
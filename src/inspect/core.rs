@@ -0,0 +1,353 @@
+//! Core functionality for inspection commands
+
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use super::types::{InspectConfig, PathsConfig};
+use crate::document;
+use crate::error::JsonExtractError;
+use crate::get::utils::{find_nested_value, value_type_name};
+use crate::path::{child_path, format_path, index_path, parse_path};
+use crate::source::read_source;
+
+/// Check whether the path of `config` exists in a file (`-` reads stdin)
+pub fn has_field(config: &InspectConfig) -> Result<bool> {
+    let content = read_source(&config.file_path)?;
+    has_field_from_str(&content, config)
+}
+
+/// Check whether the path of `config` exists in document text
+pub fn has_field_from_str(content: &str, config: &InspectConfig) -> Result<bool> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    match &config.field_path {
+        Some(path) => Ok(has(&value, path)?),
+        None => Ok(true),
+    }
+}
+
+/// Type name of the value selected by `config` in a file (`-` reads stdin)
+pub fn field_type(config: &InspectConfig) -> Result<&'static str> {
+    let content = read_source(&config.file_path)?;
+    field_type_from_str(&content, config)
+}
+
+/// Type name of the value selected by `config` in document text
+pub fn field_type_from_str(content: &str, config: &InspectConfig) -> Result<&'static str> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    Ok(value_type_name(document::select(
+        &value,
+        config.field_path.as_deref(),
+    )?))
+}
+
+/// Length of the value selected by `config` in a file (`-` reads stdin)
+pub fn field_length(config: &InspectConfig) -> Result<usize> {
+    let content = read_source(&config.file_path)?;
+    field_length_from_str(&content, config)
+}
+
+/// Length of the value selected by `config` in document text
+pub fn field_length_from_str(content: &str, config: &InspectConfig) -> Result<usize> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let path = config.field_path.as_deref().unwrap_or(".");
+    Ok(length(
+        document::select(&value, config.field_path.as_deref())?,
+        path,
+    )?)
+}
+
+/// Keys of the object (or indexes of the array) selected by `config` in a file
+pub fn field_keys(config: &InspectConfig) -> Result<Vec<String>> {
+    Ok(field_entries(config)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect())
+}
+
+/// Values of the object or array selected by `config` in a file
+pub fn field_values(config: &InspectConfig) -> Result<Vec<JsonValue>> {
+    Ok(field_entries(config)?
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect())
+}
+
+/// Key/value pairs of the object or array selected by `config` in a file
+/// (`-` reads stdin)
+pub fn field_entries(config: &InspectConfig) -> Result<Vec<(String, JsonValue)>> {
+    let content = read_source(&config.file_path)?;
+    field_entries_from_str(&content, config)
+}
+
+/// Key/value pairs of the object or array selected by `config` in document text
+pub fn field_entries_from_str(
+    content: &str,
+    config: &InspectConfig,
+) -> Result<Vec<(String, JsonValue)>> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let path = config.field_path.as_deref().unwrap_or(".");
+    Ok(entries(
+        document::select(&value, config.field_path.as_deref())?,
+        path,
+        config.nested,
+    )?
+    .into_iter()
+    .map(|(key, value)| (key, value.clone()))
+    .collect())
+}
+
+/// Leaf paths and values of a file (`-` reads stdin), see [`leaf_paths`]
+pub fn list_paths(config: &PathsConfig) -> Result<Vec<(String, JsonValue)>> {
+    let content = read_source(&config.file_path)?;
+    list_paths_from_str(&content, config)
+}
+
+/// Leaf paths and values of document text, see [`leaf_paths`]
+pub fn list_paths_from_str(
+    content: &str,
+    config: &PathsConfig,
+) -> Result<Vec<(String, JsonValue)>> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let start = document::select(&value, config.field_path.as_deref())?;
+    let prefix = match &config.field_path {
+        Some(path) => format_path(&parse_path(path)?),
+        None => String::new(),
+    };
+    let pattern = config.pattern.as_deref().map(glob_regex).transpose()?;
+
+    Ok(leaf_paths(start, &prefix, config.max_depth)
+        .into_iter()
+        .filter(|(path, _)| pattern.as_ref().is_none_or(|regex| regex.is_match(path)))
+        .map(|(path, value)| (path, value.clone()))
+        .collect())
+}
+
+/// Whether `path` exists in `value`
+///
+/// Malformed paths and indexing into a non-array are errors, not absence.
+pub fn has(value: &JsonValue, path: &str) -> Result<bool, JsonExtractError> {
+    Ok(find_nested_value(value, path)?.is_some())
+}
+
+/// Number of elements of an array, entries of an object, or characters of a string
+///
+/// `path` names the value in the error reported for other types.
+pub fn length(value: &JsonValue, path: &str) -> Result<usize, JsonExtractError> {
+    match value {
+        JsonValue::Array(items) => Ok(items.len()),
+        JsonValue::Object(map) => Ok(map.len()),
+        JsonValue::String(s) => Ok(s.chars().count()),
+        other => Err(JsonExtractError::InvalidValueType(format!(
+            "{} at {} has no length",
+            value_type_name(other),
+            path
+        ))),
+    }
+}
+
+/// Children of an object (by key) or an array (by index), in document order
+///
+/// With `nested`, objects and arrays are descended into and every leaf is
+/// listed with its path relative to `value` (`scripts.build`, `files[0]`);
+/// empty objects and arrays count as leaves. `path` names the value in the
+/// error reported for scalars.
+pub fn entries<'a>(
+    value: &'a JsonValue,
+    path: &str,
+    nested: bool,
+) -> Result<Vec<(String, &'a JsonValue)>, JsonExtractError> {
+    let children: Vec<(String, &JsonValue)> = match value {
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(key, child)| (key.clone(), child))
+            .collect(),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, child)| (i.to_string(), child))
+            .collect(),
+        other => {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "{} at {} has no keys",
+                value_type_name(other),
+                path
+            )))
+        }
+    };
+    if !nested {
+        return Ok(children);
+    }
+
+    Ok(leaf_paths(value, "", None))
+}
+
+/// Every leaf below `value` with its path, in document order
+///
+/// Paths start with `prefix` (empty for the document root) and use the field
+/// path syntax, so they can be passed back to `get` and `set`. Scalars and
+/// empty objects and arrays are leaves; with `max_depth`, values that many
+/// segments below `value` are listed as leaves too.
+pub fn leaf_paths<'a>(
+    value: &'a JsonValue,
+    prefix: &str,
+    max_depth: Option<usize>,
+) -> Vec<(String, &'a JsonValue)> {
+    fn visit<'a>(
+        value: &'a JsonValue,
+        path: String,
+        depth_left: Option<usize>,
+        out: &mut Vec<(String, &'a JsonValue)>,
+    ) {
+        let depth_left = match depth_left {
+            Some(0) => return out.push((path, value)),
+            depth_left => depth_left.map(|depth| depth - 1),
+        };
+        match value {
+            JsonValue::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    visit(child, child_path(&path, key), depth_left, out);
+                }
+            }
+            JsonValue::Array(items) if !items.is_empty() => {
+                for (i, child) in items.iter().enumerate() {
+                    visit(child, index_path(&path, i), depth_left, out);
+                }
+            }
+            _ => out.push((path, value)),
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(value, prefix.to_string(), max_depth, &mut out);
+    out
+}
+
+/// Regex matching the whole text against a glob with `*` and `?` wildcards
+fn glob_regex(pattern: &str) -> Result<Regex, JsonExtractError> {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|e| JsonExtractError::InvalidPattern(format!("{}: {}", pattern, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get::utils::get_nested_value;
+
+    const PACKAGE: &str = r#"{"name": "démo", "files": ["dist"], "scripts": {}, "private": true}"#;
+
+    fn config(field_path: &str) -> InspectConfig {
+        InspectConfig {
+            field_path: Some(field_path.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_has_and_type() {
+        assert!(has_field_from_str(PACKAGE, &config("files[0]")).unwrap());
+        assert!(!has_field_from_str(PACKAGE, &config("files[1]")).unwrap());
+        assert!(!has_field_from_str(PACKAGE, &config("publishConfig.registry")).unwrap());
+        assert!(has_field_from_str(PACKAGE, &config("files[x]")).is_err());
+
+        assert_eq!(
+            field_type_from_str(PACKAGE, &config("private")).unwrap(),
+            "boolean"
+        );
+        assert_eq!(
+            field_type_from_str(PACKAGE, &InspectConfig::default()).unwrap(),
+            "object"
+        );
+        assert!(field_type_from_str(PACKAGE, &config("missing")).is_err());
+    }
+
+    #[test]
+    fn test_entries() {
+        let content = r#"{"scripts": {"build": "tsc", "test": "jest"}, "files": ["dist", {}]}"#;
+        let keys = |config: &InspectConfig| -> Vec<String> {
+            field_entries_from_str(content, config)
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect()
+        };
+
+        assert_eq!(keys(&config("scripts")), ["build", "test"]);
+        assert_eq!(keys(&config("files")), ["0", "1"]);
+        assert_eq!(
+            keys(&InspectConfig {
+                nested: true,
+                ..Default::default()
+            }),
+            ["scripts.build", "scripts.test", "files[0]", "files[1]"]
+        );
+        assert_eq!(
+            field_entries_from_str(content, &config("scripts")).unwrap()[1],
+            ("test".to_string(), serde_json::json!("jest"))
+        );
+        assert!(field_entries_from_str(content, &config("scripts.build")).is_err());
+    }
+
+    #[test]
+    fn test_paths_round_trip() {
+        let content = r#"{"exports": {"./package.json": "./package.json", ".": {"import": "./index.mjs"}}, "files": [[1], {}]}"#;
+        let config = PathsConfig::default();
+        let paths = list_paths_from_str(content, &config).unwrap();
+
+        assert_eq!(
+            paths
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>(),
+            [
+                r#"exports["./package.json"]"#,
+                r#"exports["."].import"#,
+                "files[0][0]",
+                "files[1]",
+            ]
+        );
+        let document: JsonValue = serde_json::from_str(content).unwrap();
+        for (path, value) in &paths {
+            assert_eq!(get_nested_value(&document, path).unwrap(), value);
+        }
+
+        let config = PathsConfig {
+            field_path: Some("files".to_string()),
+            max_depth: Some(1),
+            pattern: Some("*[0]".to_string()),
+            ..config
+        };
+        assert_eq!(
+            list_paths_from_str(content, &config).unwrap(),
+            [("files[0]".to_string(), serde_json::json!([1]))]
+        );
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!(field_length_from_str(PACKAGE, &config("files")).unwrap(), 1);
+        assert_eq!(
+            field_length_from_str(PACKAGE, &config("scripts")).unwrap(),
+            0
+        );
+        assert_eq!(field_length_from_str(PACKAGE, &config("name")).unwrap(), 4);
+        assert_eq!(
+            field_length_from_str(PACKAGE, &InspectConfig::default()).unwrap(),
+            4
+        );
+        assert_eq!(
+            field_length_from_str(PACKAGE, &config("private"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid value type: boolean at private has no length"
+        );
+    }
+}
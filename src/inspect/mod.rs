@@ -0,0 +1,13 @@
+//! Value inspection functionality
+//!
+//! Answers the common questions scripts ask about a document without parsing
+//! `get` output: whether a path exists, the type of its value, its length, and
+//! the keys, values and entries of objects and arrays.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
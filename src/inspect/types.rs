@@ -0,0 +1,55 @@
+//! Type definitions for inspection commands
+
+use crate::format::Format;
+
+/// Configuration for inspecting a value in a document
+#[derive(Debug, Clone)]
+pub struct InspectConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Dot-separated path of the inspected value (None for the whole document)
+    pub field_path: Option<String>,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// List the leaf paths of nested objects and arrays instead of the
+    /// direct children (keys, values and entries)
+    pub nested: bool,
+}
+
+impl Default for InspectConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: None,
+            format: None,
+            nested: false,
+        }
+    }
+}
+
+/// Configuration for listing the leaf paths of a document
+#[derive(Debug, Clone)]
+pub struct PathsConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Dot-separated path of the listed sub-tree (None for the whole document)
+    pub field_path: Option<String>,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// Stop descending this many segments below the listed value
+    pub max_depth: Option<usize>,
+    /// Only list paths matching this glob (`*` matches any text, `?` one character)
+    pub pattern: Option<String>,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: None,
+            format: None,
+            max_depth: None,
+            pattern: None,
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! CLI definitions and handling for has, type, length, keys, values, entries
+//! and paths commands
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use serde_json::Value as JsonValue;
+
+use super::core::{field_entries, field_length, field_type, has_field_from_str, list_paths};
+use super::types::{InspectConfig, PathsConfig};
+use crate::error::ExitStatus;
+use crate::format::xcli::{format_arg, selected_format};
+use crate::get::utils::{format_field, value_type_name};
+use crate::source::read_source;
+
+/// Exit status of `has` when a path does not exist (same as a missing field)
+const MISSING_STATUS: i32 = 4;
+
+/// Define the has command CLI structure
+pub fn has_command() -> Command {
+    input_args(Command::new("has"))
+        .about("Exit with status 0 if every path exists, 4 otherwise (prints nothing)")
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Dot-separated field path (can be used multiple times)")
+                .action(clap::ArgAction::Append)
+                .required(true),
+        )
+}
+
+/// Define the type command CLI structure
+pub fn type_command() -> Command {
+    input_args(Command::new("type"))
+        .about("Print the type of a value: object, array, string, number, boolean or null")
+        .arg(field_arg())
+}
+
+/// Define the length command CLI structure
+pub fn length_command() -> Command {
+    input_args(Command::new("length"))
+        .about("Print the length of an array, object or string")
+        .arg(field_arg())
+}
+
+/// Define the keys command CLI structure
+pub fn keys_command() -> Command {
+    listing_args(Command::new("keys"))
+        .about("List the keys of an object or the indexes of an array")
+}
+
+/// Define the values command CLI structure
+pub fn values_command() -> Command {
+    listing_args(Command::new("values"))
+        .about("List the values of an object or array")
+        .arg(raw_output_arg())
+}
+
+/// Define the entries command CLI structure
+pub fn entries_command() -> Command {
+    listing_args(Command::new("entries"))
+        .about("List the key/value pairs of an object or array")
+        .arg(raw_output_arg())
+}
+
+/// Define the paths command CLI structure
+pub fn paths_command() -> Command {
+    input_args(Command::new("paths"))
+        .about("List the path of every leaf value, in the syntax accepted by get and set")
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("List only the leaves below this path (default: the whole document)"),
+        )
+        .arg(
+            Arg::new("max-depth")
+                .short('d')
+                .long("max-depth")
+                .value_name("N")
+                .help("Stop descending N levels below the listed value")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("match")
+                .short('g')
+                .long("match")
+                .value_name("GLOB")
+                .help("Only list paths matching GLOB ('*' matches any text, '?' one character)"),
+        )
+        .arg(
+            Arg::new("types")
+                .short('t')
+                .long("types")
+                .help("Add a tab and the type of each value")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("values")
+                .short('v')
+                .long("values")
+                .help("Add a tab and each value as compact JSON")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Add the arguments shared by the keys, values and entries commands
+fn listing_args(command: Command) -> Command {
+    input_args(command)
+        .arg(field_arg())
+        .arg(
+            Arg::new("nested")
+                .long("nested")
+                .help("Descend into nested objects and arrays and list every leaf by its path (e.g. scripts.build, files[0])")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FORMAT")
+                .help("Output format: one item per line, or a JSON array (an object for entries)")
+                .value_parser(["lines", "json", "json-pretty"])
+                .default_value("lines"),
+        )
+}
+
+fn raw_output_arg() -> Arg {
+    Arg::new("raw-output")
+        .short('r')
+        .long("raw-output")
+        .help("Print string values without quotes (lines output)")
+        .action(clap::ArgAction::SetTrue)
+}
+
+/// Add the input file and format arguments shared by the inspection commands
+fn input_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("Input file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(format_arg())
+}
+
+fn field_arg() -> Arg {
+    Arg::new("field")
+        .short('k')
+        .long("field")
+        .value_name("FIELD")
+        .help("Dot-separated field path (default: the whole document)")
+}
+
+/// Build the configuration for one field path from the shared arguments
+fn config(matches: &ArgMatches, field_path: Option<&String>) -> Result<InspectConfig> {
+    Ok(InspectConfig {
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        field_path: field_path.cloned(),
+        nested: false,
+        format: selected_format(matches)?,
+    })
+}
+
+/// Handle has command logic
+pub fn handle_has_command(matches: &ArgMatches) -> Result<()> {
+    // Read once so that stdin can be checked for several paths
+    let content = read_source(matches.get_one::<String>("file").unwrap())?;
+    for field_path in matches.get_many::<String>("field").unwrap() {
+        if !has_field_from_str(&content, &config(matches, Some(field_path))?)? {
+            return Err(ExitStatus(MISSING_STATUS).into());
+        }
+    }
+    Ok(())
+}
+
+/// Handle type command logic
+pub fn handle_type_command(matches: &ArgMatches) -> Result<()> {
+    let config = config(matches, matches.get_one::<String>("field"))?;
+    println!("{}", field_type(&config)?);
+    Ok(())
+}
+
+/// Handle length command logic
+pub fn handle_length_command(matches: &ArgMatches) -> Result<()> {
+    let config = config(matches, matches.get_one::<String>("field"))?;
+    println!("{}", field_length(&config)?);
+    Ok(())
+}
+
+/// Handle keys command logic
+pub fn handle_keys_command(matches: &ArgMatches) -> Result<()> {
+    handle_listing(
+        matches,
+        |key, _| Ok(key.to_string()),
+        |entries| {
+            entries
+                .into_iter()
+                .map(|(key, _)| JsonValue::String(key))
+                .collect()
+        },
+    )
+}
+
+/// Handle values command logic
+pub fn handle_values_command(matches: &ArgMatches) -> Result<()> {
+    let raw = matches.get_flag("raw-output");
+    handle_listing(
+        matches,
+        |_, value| format_field(value, None, raw),
+        |entries| JsonValue::Array(entries.into_iter().map(|(_, value)| value).collect()),
+    )
+}
+
+/// Handle entries command logic
+pub fn handle_entries_command(matches: &ArgMatches) -> Result<()> {
+    let raw = matches.get_flag("raw-output");
+    handle_listing(
+        matches,
+        |key, value| Ok(format!("{}: {}", key, format_field(value, None, raw)?)),
+        |entries| JsonValue::Object(entries.into_iter().collect()),
+    )
+}
+
+/// Print a keys, values or entries listing, one line per entry or as JSON
+fn handle_listing(
+    matches: &ArgMatches,
+    line: impl Fn(&str, &JsonValue) -> Result<String>,
+    json: impl FnOnce(Vec<(String, JsonValue)>) -> JsonValue,
+) -> Result<()> {
+    let config = InspectConfig {
+        nested: matches.get_flag("nested"),
+        ..config(matches, matches.get_one::<String>("field"))?
+    };
+    let entries = field_entries(&config)?;
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "lines" => {
+            for (key, value) in &entries {
+                println!("{}", line(key, value)?);
+            }
+        }
+        "json-pretty" => println!("{}", serde_json::to_string_pretty(&json(entries))?),
+        _ => println!("{}", json(entries)),
+    }
+    Ok(())
+}
+
+/// Handle paths command logic
+pub fn handle_paths_command(matches: &ArgMatches) -> Result<()> {
+    let input = config(matches, matches.get_one::<String>("field"))?;
+    let config = PathsConfig {
+        file_path: input.file_path,
+        field_path: input.field_path,
+        format: input.format,
+        max_depth: matches
+            .get_one::<u64>("max-depth")
+            .map(|&depth| depth as usize),
+        pattern: matches.get_one::<String>("match").cloned(),
+    };
+    let types = matches.get_flag("types");
+    let values = matches.get_flag("values");
+
+    for (path, value) in list_paths(&config)? {
+        let mut line = path;
+        if types {
+            line = format!("{}\t{}", line, value_type_name(&value));
+        }
+        if values {
+            line = format!("{}\t{}", line, value);
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
@@ -12,9 +12,29 @@
 pub mod error;
 pub use error::JsonExtractError;
 
+pub mod batch;
+pub mod bump;
+pub mod convert;
+pub mod document;
+pub mod env;
+pub mod flatten;
+pub mod format;
 pub mod get;
+pub mod inspect;
+pub mod ndjson;
+pub mod path;
+pub mod render;
+pub mod search;
 pub mod set;
+pub mod source;
 
 // Re-export core types for convenience
+pub use bump::types::BumpConfig;
+pub use convert::types::ConvertConfig;
+pub use env::types::EnvConfig;
+pub use flatten::types::FlattenConfig;
 pub use get::types::ExtractConfig;
+pub use inspect::types::InspectConfig;
+pub use render::types::RenderConfig;
+pub use search::types::SearchConfig;
 pub use set::types::SetConfig;
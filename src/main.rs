@@ -2,16 +2,55 @@
 
 use anyhow::{Context, Result};
 use clap::Command;
-use i_edit_json::{get::xcli::get_command, set::xcli::cli as set_command};
+use i_edit_json::{
+    bump::xcli::bump_command,
+    convert::xcli::convert_command,
+    env::xcli::env_command,
+    error::{exit_code, ExitStatus},
+    flatten::xcli::{flatten_command, unflatten_command},
+    get::xcli::get_command,
+    inspect::xcli::{
+        entries_command, has_command, keys_command, length_command, paths_command, type_command,
+        values_command,
+    },
+    render::xcli::render_command,
+    search::xcli::find_command,
+    set::xcli::{cli as set_command, sort_command},
+};
 
-fn main() -> Result<()> {
+fn main() {
+    if let Err(e) = run() {
+        // Failures already reported by the command only set the exit status
+        if !e.chain().any(|cause| cause.is::<ExitStatus>()) {
+            eprintln!("Error: {:?}", e);
+        }
+        std::process::exit(exit_code(&e));
+    }
+}
+
+fn run() -> Result<()> {
     // Define main CLI structure
     let mut app = Command::new("i_edit_json")
         .version(env!("CARGO_PKG_VERSION"))
         .author("YeMiancheng <ymc.github@gmail.com>")
         .about("A lightweight, high-performance JSON field extraction and manipulation tool")
         .subcommand(get_command().name("get"))
-        .subcommand(set_command().name("set"));
+        .subcommand(set_command().name("set"))
+        .subcommand(sort_command().name("sort"))
+        .subcommand(bump_command().name("bump"))
+        .subcommand(convert_command().name("convert"))
+        .subcommand(has_command().name("has"))
+        .subcommand(type_command().name("type"))
+        .subcommand(length_command().name("length"))
+        .subcommand(keys_command().name("keys"))
+        .subcommand(values_command().name("values"))
+        .subcommand(entries_command().name("entries"))
+        .subcommand(paths_command().name("paths"))
+        .subcommand(flatten_command().name("flatten"))
+        .subcommand(unflatten_command().name("unflatten"))
+        .subcommand(find_command().name("find"))
+        .subcommand(env_command().name("env"))
+        .subcommand(render_command().name("render"));
 
     // Parse CLI arguments
     let matches = app.clone().get_matches();
@@ -26,6 +65,66 @@ fn main() -> Result<()> {
             i_edit_json::set::xcli::handle_set_command(sub_matches)
                 .context("Failed to execute set command")?;
         }
+        Some(("sort", sub_matches)) => {
+            i_edit_json::set::xcli::handle_sort_command(sub_matches)
+                .context("Failed to execute sort command")?;
+        }
+        Some(("bump", sub_matches)) => {
+            i_edit_json::bump::xcli::handle_bump_command(sub_matches)
+                .context("Failed to execute bump command")?;
+        }
+        Some(("convert", sub_matches)) => {
+            i_edit_json::convert::xcli::handle_convert_command(sub_matches)
+                .context("Failed to execute convert command")?;
+        }
+        Some(("has", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_has_command(sub_matches)
+                .context("Failed to execute has command")?;
+        }
+        Some(("type", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_type_command(sub_matches)
+                .context("Failed to execute type command")?;
+        }
+        Some(("length", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_length_command(sub_matches)
+                .context("Failed to execute length command")?;
+        }
+        Some(("keys", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_keys_command(sub_matches)
+                .context("Failed to execute keys command")?;
+        }
+        Some(("values", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_values_command(sub_matches)
+                .context("Failed to execute values command")?;
+        }
+        Some(("entries", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_entries_command(sub_matches)
+                .context("Failed to execute entries command")?;
+        }
+        Some(("paths", sub_matches)) => {
+            i_edit_json::inspect::xcli::handle_paths_command(sub_matches)
+                .context("Failed to execute paths command")?;
+        }
+        Some(("flatten", sub_matches)) => {
+            i_edit_json::flatten::xcli::handle_flatten_command(sub_matches)
+                .context("Failed to execute flatten command")?;
+        }
+        Some(("unflatten", sub_matches)) => {
+            i_edit_json::flatten::xcli::handle_unflatten_command(sub_matches)
+                .context("Failed to execute unflatten command")?;
+        }
+        Some(("find", sub_matches)) => {
+            i_edit_json::search::xcli::handle_find_command(sub_matches)
+                .context("Failed to execute find command")?;
+        }
+        Some(("env", sub_matches)) => {
+            i_edit_json::env::xcli::handle_env_command(sub_matches)
+                .context("Failed to execute env command")?;
+        }
+        Some(("render", sub_matches)) => {
+            i_edit_json::render::xcli::handle_render_command(sub_matches)
+                .context("Failed to execute render command")?;
+        }
         _ => {
             // Print help if no subcommand is provided
             println!("{}", app.render_help());
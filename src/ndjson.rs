@@ -0,0 +1,209 @@
+//! JSON Lines (NDJSON) streaming
+//!
+//! Every non-empty line is a separate JSON document; blank lines are kept when
+//! a stream is rewritten. Records are read, edited and written one line at a
+//! time, so memory use does not depend on the size of the input.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use crate::error::JsonExtractError;
+use crate::source::{display_name, parse_json, STDIO_PATH};
+
+/// One record of a JSON Lines stream
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// 1-based line number in the input
+    pub line: usize,
+    /// Text of the line without its line break
+    pub text: String,
+}
+
+impl Record {
+    /// Parse the record, reporting `source:line` on syntax errors
+    pub fn parse(&self, source: &str) -> Result<JsonValue, JsonExtractError> {
+        parse_json(&self.text, &self.location(source))
+    }
+
+    /// Whether the line holds only whitespace
+    fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// `source:line` label used in messages about this record
+    pub fn location(&self, source: &str) -> String {
+        format!("{}:{}", display_name(source), self.line)
+    }
+}
+
+/// Counts reported after rewriting a stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RewriteSummary {
+    /// Records the edit was applied to
+    pub updated: usize,
+    /// Records copied unchanged because a guard did not hold
+    pub skipped: usize,
+}
+
+/// Open a file, or standard input for `-`, for reading line by line
+pub fn open_reader(path: &str) -> Result<Box<dyn BufRead>> {
+    if path == STDIO_PATH {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        let file = File::open(path).with_context(|| format!("Failed to read file: {}", path))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Iterate over the non-empty lines of a JSON Lines stream
+pub fn records(reader: impl BufRead) -> impl Iterator<Item = io::Result<Record>> {
+    lines(reader).filter(|record| !matches!(record, Ok(record) if record.is_blank()))
+}
+
+/// Every line of a stream, blank ones included
+fn lines(reader: impl BufRead) -> impl Iterator<Item = io::Result<Record>> {
+    reader.lines().enumerate().map(|(index, text)| {
+        text.map(|text| Record {
+            line: index + 1,
+            text,
+        })
+    })
+}
+
+/// Apply `edit` to every record of `reader` and write one compact line per record
+///
+/// Blank lines, and records for which `edit` fails with
+/// [`JsonExtractError::ConditionNotMet`], are copied unchanged; any other
+/// error stops the rewrite.
+pub fn rewrite(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    source: &str,
+    mut edit: impl FnMut(&mut JsonValue) -> Result<()>,
+) -> Result<RewriteSummary> {
+    let mut summary = RewriteSummary::default();
+    for record in lines(reader) {
+        let record = record.with_context(|| format!("Failed to read {}", display_name(source)))?;
+        if record.is_blank() {
+            writeln!(writer, "{}", record.text)?;
+            continue;
+        }
+        let mut value = record.parse(source)?;
+
+        match edit(&mut value) {
+            Ok(()) => {
+                serde_json::to_writer(&mut writer, &value)?;
+                summary.updated += 1;
+            }
+            Err(e) if is_condition_not_met(&e) => {
+                writer.write_all(record.text.as_bytes())?;
+                summary.skipped += 1;
+            }
+            Err(e) => return Err(e.context(record.location(source))),
+        }
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(summary)
+}
+
+/// Rewrite the records of `input` into `output` (`-` for stdin/stdout)
+///
+/// When `output` is the input file itself, records are streamed into a
+/// temporary file next to it that then replaces the original. Records for
+/// stdout are held back until every one of them has been rewritten.
+pub fn rewrite_path(
+    input: &str,
+    output: &str,
+    edit: impl FnMut(&mut JsonValue) -> Result<()>,
+) -> Result<RewriteSummary> {
+    let reader = open_reader(input)?;
+    if output == STDIO_PATH {
+        // Buffered so that a record failing halfway prints nothing
+        let mut buffer = Vec::new();
+        let summary = rewrite(reader, &mut buffer, input, edit)?;
+        io::stdout().lock().write_all(&buffer)?;
+        return Ok(summary);
+    }
+
+    let in_place = input == output;
+    let target = if in_place {
+        format!("{}.tmp", output)
+    } else {
+        output.to_string()
+    };
+    let file =
+        File::create(&target).with_context(|| format!("Failed to write to file: {}", target))?;
+    let summary = rewrite(reader, BufWriter::new(file), input, edit).inspect_err(|_| {
+        if in_place {
+            let _ = fs::remove_file(&target);
+        }
+    })?;
+    if in_place {
+        fs::rename(&target, output)
+            .with_context(|| format!("Failed to write to file: {}", output))?;
+    }
+    Ok(summary)
+}
+
+/// Whether an error chain stems from an unmet update guard
+fn is_condition_not_met(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<JsonExtractError>(),
+            Some(JsonExtractError::ConditionNotMet(_))
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_records_skip_blank_lines() {
+        let input = "{\"a\": 1}\n\n{\"a\": 2}\n";
+        let records: Vec<Record> = records(input.as_bytes()).map(Result::unwrap).collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].line, 3);
+        assert_eq!(records[1].parse("log.jsonl").unwrap(), json!({"a": 2}));
+        assert!(Record {
+            line: 7,
+            text: "{".to_string()
+        }
+        .parse("log.jsonl")
+        .unwrap_err()
+        .to_string()
+        .contains("log.jsonl:7"));
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let input = "{\"n\": 1, \"tag\": \"a\"}\n\n{\"n\": 2}\n";
+        let mut output = Vec::new();
+        let summary = rewrite(input.as_bytes(), &mut output, "-", |value| {
+            if value["n"] == json!(2) {
+                return Err(JsonExtractError::ConditionNotMet("n".to_string()).into());
+            }
+            value["n"] = json!(10);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"n\":10,\"tag\":\"a\"}\n\n{\"n\": 2}\n"
+        );
+        assert_eq!(
+            summary,
+            RewriteSummary {
+                updated: 1,
+                skipped: 1
+            }
+        );
+    }
+}
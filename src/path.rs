@@ -0,0 +1,194 @@
+//! Field path syntax
+//!
+//! A path is a list of object keys and array indexes: `dependencies.serde`,
+//! `authors[0].name`, `matrix[1][0]`. Keys that are empty, contain `.`, `[`,
+//! `]` or control characters, or start or end with whitespace are written as
+//! a JSON string in brackets, e.g.
+//! `exports["./package.json"]`; [`format_path`] produces this form, so printed
+//! paths can be passed back to `get` and `set`.
+
+use crate::error::JsonExtractError;
+
+/// One step of a field path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// Object key
+    Key(String),
+    /// Array index
+    Index(usize),
+}
+
+/// Split a field path into keys and array indexes
+pub fn parse_path(path: &str) -> Result<Vec<Segment>, JsonExtractError> {
+    let invalid =
+        |reason: &str| JsonExtractError::InvalidFieldPath(format!("{}: {}", reason, path));
+    if path.is_empty() {
+        return Err(JsonExtractError::InvalidFieldPath(
+            "Field path cannot be empty".to_string(),
+        ));
+    }
+
+    let mut segments = Vec::new();
+    let mut rest = path;
+    loop {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let (segment, after) = bracket(inner).ok_or_else(|| invalid("Unclosed bracket"))??;
+            segments.push(segment);
+            rest = after;
+        } else {
+            let end = rest.find(['.', '[', ']']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(JsonExtractError::InvalidFieldPath(if rest.is_empty() {
+                    "Field path cannot end with a dot".to_string()
+                } else {
+                    "Empty path segment".to_string()
+                }));
+            }
+            segments.push(Segment::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+
+        // A segment is followed by the end, a dot and a key, or a bracket
+        match rest.chars().next() {
+            None => return Ok(segments),
+            Some('.') => {
+                rest = &rest[1..];
+                if rest.starts_with('[') {
+                    return Err(invalid("Empty path segment"));
+                }
+            }
+            Some('[') => {}
+            Some(c) => return Err(invalid(&format!("Unexpected '{}'", c))),
+        }
+    }
+}
+
+/// Parse the inside of `[...]`, returning the segment and the text after `]`
+///
+/// None when the bracket is not closed.
+fn bracket(inner: &str) -> Option<Result<(Segment, &str), JsonExtractError>> {
+    if inner.starts_with('"') {
+        // Find the closing quote, skipping escaped characters
+        let mut escaped = false;
+        let end = inner
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| {
+                let close = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                close
+            })?
+            .0;
+        let key = match serde_json::from_str::<String>(&inner[..=end]) {
+            Ok(key) => key,
+            Err(e) => {
+                return Some(Err(JsonExtractError::InvalidFieldPath(format!(
+                    "Invalid quoted key {}: {}",
+                    &inner[..=end],
+                    e
+                ))))
+            }
+        };
+        let after = inner[end + 1..].strip_prefix(']')?;
+        return Some(Ok((Segment::Key(key), after)));
+    }
+
+    let end = inner.find(']')?;
+    let index_str = &inner[..end];
+    Some(
+        index_str
+            .parse::<usize>()
+            .map(|index| (Segment::Index(index), &inner[end + 1..]))
+            .map_err(|_| {
+                JsonExtractError::InvalidArrayIndex(format!("Invalid array index: {}", index_str))
+            }),
+    )
+}
+
+/// Write segments in the field path syntax, quoting keys where needed
+pub fn format_path(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .fold(String::new(), |path, segment| match segment {
+            Segment::Key(key) => child_path(&path, key),
+            Segment::Index(index) => index_path(&path, *index),
+        })
+}
+
+/// Path of the key `key` of the object at `parent` (empty for the root)
+pub fn child_path(parent: &str, key: &str) -> String {
+    if needs_quotes(key) {
+        let quoted = serde_json::to_string(key).unwrap_or_default();
+        format!("{}[{}]", parent, quoted)
+    } else if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Whether `key` must be written as a quoted JSON string to read back as is
+fn needs_quotes(key: &str) -> bool {
+    key.is_empty()
+        || key.contains(['.', '[', ']'])
+        || key.chars().any(char::is_control)
+        || key.starts_with(char::is_whitespace)
+        || key.ends_with(char::is_whitespace)
+}
+
+/// Path of the element `index` of the array at `parent` (empty for the root)
+pub fn index_path(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> Segment {
+        Segment::Key(key.to_string())
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("authors[0].name").unwrap(),
+            [key("authors"), Segment::Index(0), key("name")]
+        );
+        assert_eq!(
+            parse_path(r#"exports["./package.json"]["a\"b"]"#).unwrap(),
+            [key("exports"), key("./package.json"), key("a\"b")]
+        );
+        assert_eq!(
+            parse_path("[1][0]").unwrap(),
+            [Segment::Index(1), Segment::Index(0)]
+        );
+        for invalid in [
+            "", "a..b", "a.", "a[0", "a[x]", "a[0]b", "a.[0]", r#"a["b]"#,
+        ] {
+            assert!(parse_path(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_format_path_round_trips() {
+        let segments = [
+            key("scripts"),
+            key("build:prod"),
+            key(""),
+            key("a.b"),
+            Segment::Index(2),
+            key("[x]"),
+            key("say \"hi\""),
+            key("k\n"),
+            key(" padded "),
+            key("tab\there"),
+        ];
+        let path = format_path(&segments);
+        assert_eq!(
+            path,
+            r#"scripts.build:prod[""]["a.b"][2]["[x]"].say "hi"["k\n"][" padded "]["tab\there"]"#
+        );
+        assert_eq!(parse_path(&path).unwrap(), segments);
+    }
+}
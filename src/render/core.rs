@@ -0,0 +1,208 @@
+//! Core functionality for render command
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+
+use super::types::RenderConfig;
+use crate::error::JsonExtractError;
+use crate::format::Format;
+use crate::get::utils::{find_nested_value, plain_text, value_type_name};
+use crate::source::read_source;
+
+/// Render a template file with the fields of a document (either may be `-`)
+pub fn render(config: &RenderConfig) -> Result<String> {
+    let template = read_source(&config.template_path)?;
+    let content = read_source(&config.file_path)?;
+    let value =
+        Format::resolve(config.format, &config.file_path).parse(&content, &config.file_path)?;
+    render_str(&template, &value)
+}
+
+/// Replace every `{{ expression }}` in `template` with its value
+///
+/// An expression is a field path or a quoted string, followed by filters
+/// separated by `|`: `upper`, `lower`, `default("text")` and `join(", ")`.
+/// Strings are inserted as they are, null as nothing and other values as
+/// JSON. A missing field is an error unless a `default` fills it in.
+pub fn render_str(template: &str, value: &JsonValue) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let line = template[..template.len() - rest.len() + start]
+            .matches('\n')
+            .count()
+            + 1;
+        let inner = &rest[start + 2..];
+        let end = closing_braces(inner).ok_or_else(|| {
+            JsonExtractError::InvalidTemplate(format!("Unclosed {{{{ at line {}", line))
+        })?;
+        let expression = inner[..end].trim();
+        let text = evaluate(expression, value)
+            .with_context(|| format!("Placeholder {{{{ {} }}}} at line {}", expression, line))?;
+        output.push_str(&text);
+        rest = &inner[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Position of the `}}` that ends a placeholder, skipping quoted strings
+fn closing_braces(inner: &str) -> Option<usize> {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in inner.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '}' if !quoted && inner[i..].starts_with("}}") => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split `text` at every `separator` outside quoted strings
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut from = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&text[from..i]);
+                from = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[from..]);
+    parts
+}
+
+/// Text of one placeholder expression
+fn evaluate(expression: &str, value: &JsonValue) -> Result<String, JsonExtractError> {
+    let invalid = |reason: String| JsonExtractError::InvalidTemplate(reason);
+    let mut parts = split_unquoted(expression, '|').into_iter().map(str::trim);
+    let operand = parts.next().unwrap_or_default();
+    if operand.is_empty() {
+        return Err(invalid("Empty placeholder".to_string()));
+    }
+
+    // None while the field is missing, so a later `default` can fill it in
+    let mut current = if operand.starts_with('"') {
+        Some(literal(operand)?)
+    } else {
+        find_nested_value(value, operand)?.cloned()
+    };
+
+    for filter in parts {
+        let (name, argument) = match filter.strip_suffix(')').and_then(|f| f.split_once('(')) {
+            Some((name, argument)) => (name.trim(), Some(literal(argument.trim())?)),
+            None => (filter, None),
+        };
+        current = match (name, argument, current) {
+            ("default", Some(fallback), None | Some(JsonValue::Null)) => Some(fallback),
+            ("default", Some(_), current) => current,
+            ("upper", None, current) => {
+                current.map(|v| JsonValue::String(plain_text(&v).to_uppercase()))
+            }
+            ("lower", None, current) => {
+                current.map(|v| JsonValue::String(plain_text(&v).to_lowercase()))
+            }
+            ("join", separator, current) => match (current, separator) {
+                (Some(JsonValue::Array(items)), separator) => {
+                    let separator = separator.as_ref().map(plain_text).unwrap_or_default();
+                    let items: Vec<String> = items.iter().map(plain_text).collect();
+                    Some(JsonValue::String(items.join(&separator)))
+                }
+                (Some(other), _) => {
+                    return Err(JsonExtractError::InvalidValueType(format!(
+                        "join needs an array, not {}",
+                        value_type_name(&other)
+                    )))
+                }
+                (None, _) => None,
+            },
+            ("default", None, _) => {
+                return Err(invalid(
+                    "default needs a value, e.g. default(\"\")".to_string(),
+                ))
+            }
+            ("upper" | "lower", Some(_), _) => {
+                return Err(invalid(format!("{} takes no argument", name)))
+            }
+            _ => return Err(invalid(format!("Unknown filter: {}", filter))),
+        };
+    }
+
+    match current {
+        Some(value) => Ok(plain_text(&value)),
+        None => Err(JsonExtractError::FieldNotFound(operand.to_string())),
+    }
+}
+
+/// Value of a JSON literal argument such as `"MIT"`, `0` or `true`
+fn literal(text: &str) -> Result<JsonValue, JsonExtractError> {
+    serde_json::from_str(text)
+        .map_err(|e| JsonExtractError::InvalidTemplate(format!("Invalid literal {}: {}", text, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::exit_code;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_placeholders_and_filters() {
+        let value = json!({
+            "name": "demo",
+            "version": "1.2.0",
+            "repository": {"url": "https://example.com/demo.git"},
+            "keywords": ["json", "cli"],
+            "license": null,
+            "private": false,
+        });
+        let template = "LABEL version=\"{{ version }}\" url=\"{{repository.url}}\"\n\
+            # {{ name | upper }} ({{ keywords | join(\", \") }})\n\
+            {{ license | default(\"MIT\") | lower }} {{ homepage | default(\"none\") }} {{ private }}\n\
+            {{ \"{{\" }} raw }}";
+        assert_eq!(
+            render_str(template, &value).unwrap(),
+            "LABEL version=\"1.2.0\" url=\"https://example.com/demo.git\"\n\
+            # DEMO (json, cli)\n\
+            mit none false\n\
+            {{ raw }}"
+        );
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let value = json!({"name": "demo"});
+
+        let missing = render_str("a\n{{ version | upper }}", &value).unwrap_err();
+        assert_eq!(
+            missing.to_string(),
+            "Placeholder {{ version | upper }} at line 2"
+        );
+        assert_eq!(exit_code(&missing), 4);
+
+        for template in [
+            "{{ name",
+            "{{ name | shout }}",
+            "{{ }}",
+            "{{ name | default }}",
+        ] {
+            let error = render_str(template, &value).unwrap_err();
+            assert_eq!(exit_code(&error), 2, "{}", template);
+        }
+        let error = render_str("{{ name | join }}", &value).unwrap_err();
+        assert_eq!(exit_code(&error), 1);
+    }
+}
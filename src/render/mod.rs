@@ -0,0 +1,13 @@
+//! Template rendering functionality
+//!
+//! Fills `{{ field }}` placeholders in a text template from a document, with
+//! filters such as `{{ keywords | join(", ") }}` or
+//! `{{ license | default("MIT") | upper }}`.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
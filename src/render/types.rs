@@ -0,0 +1,24 @@
+//! Type definitions for render command
+
+use crate::format::Format;
+
+/// Configuration for rendering a template
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    /// Path to the template file (`-` for stdin)
+    pub template_path: String,
+    /// Path to the document the placeholders read from (`-` for stdin)
+    pub file_path: String,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            template_path: "-".to_string(),
+            file_path: "package.json".to_string(),
+            format: None,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! CLI definitions and handling for render command
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+use std::io::Write;
+
+use super::core::render;
+use super::types::RenderConfig;
+use crate::format::xcli::{format_arg, selected_format};
+use crate::source::{display_name, write_output, STDIO_PATH};
+
+/// Define the render command CLI structure
+pub fn render_command() -> Command {
+    Command::new("render")
+        .about("Fill {{ field }} placeholders in a template from a document")
+        .arg(
+            Arg::new("template")
+                .short('t')
+                .long("template")
+                .value_name("TEMPLATE")
+                .help("Template file path ('-' for stdin)")
+                .required(true),
+        )
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("Input file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(format_arg())
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Output file path ('-' for stdout)")
+                .default_value(STDIO_PATH),
+        )
+}
+
+/// Handle render command logic
+pub fn handle_render_command(matches: &ArgMatches) -> Result<()> {
+    let config = RenderConfig {
+        template_path: matches.get_one::<String>("template").unwrap().to_string(),
+        file_path: matches.get_one::<String>("file").unwrap().to_string(),
+        format: selected_format(matches)?,
+    };
+    if config.template_path == STDIO_PATH && config.file_path == STDIO_PATH {
+        bail!("The template and the input file cannot both be read from stdin");
+    }
+
+    let output = render(&config)?;
+    let path = matches.get_one::<String>("output").unwrap();
+    if path == STDIO_PATH {
+        // The template decides about the trailing newline
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+    } else {
+        write_output(path, &output)?;
+        println!(
+            "✅ Rendered {} into {}",
+            display_name(&config.template_path),
+            path
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Core functionality for find command
+
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value as JsonValue;
+
+use super::types::{MatchMode, SearchConfig, SearchMatch, SearchScope};
+use crate::document;
+use crate::error::JsonExtractError;
+use crate::path::{child_path, format_path, index_path, parse_path};
+use crate::source::read_source;
+
+/// Compiled comparison of a search pattern with text
+pub struct Matcher {
+    regex: Option<Regex>,
+    pattern: String,
+    mode: MatchMode,
+    ignore_case: bool,
+}
+
+impl Matcher {
+    /// Prepare a pattern; fails for an invalid regular expression
+    pub fn new(
+        pattern: &str,
+        mode: MatchMode,
+        ignore_case: bool,
+    ) -> Result<Self, JsonExtractError> {
+        let regex = match mode {
+            MatchMode::Regex => Some(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .map_err(|e| JsonExtractError::InvalidPattern(format!("{}: {}", pattern, e)))?,
+            ),
+            _ => None,
+        };
+        let pattern = if ignore_case {
+            pattern.to_lowercase()
+        } else {
+            pattern.to_string()
+        };
+        Ok(Self {
+            regex,
+            pattern,
+            mode,
+            ignore_case,
+        })
+    }
+
+    /// Whether `text` matches
+    pub fn is_match(&self, text: &str) -> bool {
+        if let Some(regex) = &self.regex {
+            return regex.is_match(text);
+        }
+        let lowered;
+        let text = if self.ignore_case {
+            lowered = text.to_lowercase();
+            &lowered
+        } else {
+            text
+        };
+        match self.mode {
+            MatchMode::Exact => text == self.pattern,
+            _ => text.contains(&self.pattern),
+        }
+    }
+}
+
+/// Search a file (`-` reads stdin)
+pub fn search(config: &SearchConfig) -> Result<Vec<SearchMatch>> {
+    let content = read_source(&config.file_path)?;
+    search_str(&content, config)
+}
+
+/// Search document text
+pub fn search_str(content: &str, config: &SearchConfig) -> Result<Vec<SearchMatch>> {
+    let value = document::parse(content, config.format, &config.file_path)?;
+    let start = document::select(&value, config.field_path.as_deref())?;
+    let prefix = match &config.field_path {
+        Some(path) => format_path(&parse_path(path)?),
+        None => String::new(),
+    };
+    let matcher = Matcher::new(&config.pattern, config.mode, config.ignore_case)?;
+
+    Ok(search_value(start, &prefix, &matcher, config.scope)
+        .into_iter()
+        .map(|(path, value)| SearchMatch {
+            path,
+            value: value.clone(),
+        })
+        .collect())
+}
+
+/// Matching keys and values below `value`, in document order
+///
+/// Paths start with `prefix` (empty for the document root). A value under a
+/// matching key is reported once, even if it matches too.
+pub fn search_value<'a>(
+    value: &'a JsonValue,
+    prefix: &str,
+    matcher: &Matcher,
+    scope: SearchScope,
+) -> Vec<(String, &'a JsonValue)> {
+    fn visit<'a>(
+        value: &'a JsonValue,
+        path: String,
+        key_matched: bool,
+        matcher: &Matcher,
+        scope: SearchScope,
+        out: &mut Vec<(String, &'a JsonValue)>,
+    ) {
+        let search_keys = scope != SearchScope::Values;
+        let search_values = scope != SearchScope::Keys;
+        match value {
+            JsonValue::Object(map) => {
+                if key_matched {
+                    out.push((path.clone(), value));
+                }
+                for (key, child) in map {
+                    let matched = search_keys && matcher.is_match(key);
+                    visit(child, child_path(&path, key), matched, matcher, scope, out);
+                }
+            }
+            JsonValue::Array(items) => {
+                if key_matched {
+                    out.push((path.clone(), value));
+                }
+                for (i, child) in items.iter().enumerate() {
+                    visit(child, index_path(&path, i), false, matcher, scope, out);
+                }
+            }
+            scalar => {
+                let value_matched = search_values
+                    && match scalar {
+                        JsonValue::String(text) => matcher.is_match(text),
+                        other => matcher.is_match(&other.to_string()),
+                    };
+                if key_matched || value_matched {
+                    out.push((path, value));
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(value, prefix.to_string(), false, matcher, scope, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{
+        "services": {
+            "api": {"url": "https://old.example.com/api", "retries": 3},
+            "cache": {"hosts": ["redis.local", "OLD.example.com"]}
+        },
+        "oldSetting": true
+    }"#;
+
+    fn paths(config: &SearchConfig) -> Vec<String> {
+        search_str(CONFIG, config)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_search_modes() {
+        let config = SearchConfig {
+            file_path: "config.json".to_string(),
+            pattern: "old.example.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(paths(&config), ["services.api.url"]);
+
+        let config = SearchConfig {
+            ignore_case: true,
+            ..config
+        };
+        assert_eq!(
+            paths(&config),
+            ["services.api.url", "services.cache.hosts[1]"]
+        );
+
+        let config = SearchConfig {
+            pattern: "^old".to_string(),
+            mode: MatchMode::Regex,
+            scope: SearchScope::Keys,
+            ..config
+        };
+        assert_eq!(paths(&config), ["oldSetting"]);
+
+        let config = SearchConfig {
+            pattern: "3".to_string(),
+            mode: MatchMode::Exact,
+            scope: SearchScope::Values,
+            field_path: Some("services".to_string()),
+            ..config
+        };
+        assert_eq!(paths(&config), ["services.api.retries"]);
+    }
+
+    #[test]
+    fn test_search_invalid_regex() {
+        let config = SearchConfig {
+            pattern: "(".to_string(),
+            mode: MatchMode::Regex,
+            ..Default::default()
+        };
+        assert!(search_str(CONFIG, &config).is_err());
+    }
+}
@@ -0,0 +1,12 @@
+//! Search functionality
+//!
+//! Finds keys and values anywhere in a document by substring, regular
+//! expression or exact match, and reports them by their field path.
+
+pub mod core;
+pub mod types;
+pub mod xcli;
+
+pub use core::*;
+pub use types::*;
+pub use xcli::*;
@@ -0,0 +1,69 @@
+//! Type definitions for find command
+
+use crate::format::Format;
+
+/// How the search pattern is compared with keys and values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// The text contains the pattern
+    #[default]
+    Substring,
+    /// The pattern is a regular expression found in the text
+    Regex,
+    /// The whole text equals the pattern
+    Exact,
+}
+
+/// Which parts of the document are searched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    /// Object keys and scalar values
+    #[default]
+    Both,
+    /// Object keys only
+    Keys,
+    /// Scalar values only (numbers, booleans and null as their JSON text)
+    Values,
+}
+
+/// Configuration for searching a document
+#[derive(Debug, Clone)]
+pub struct SearchConfig {
+    /// Path to the input file (`-` for stdin)
+    pub file_path: String,
+    /// Input format (None to detect from the file name)
+    pub format: Option<Format>,
+    /// Dot-separated path of the searched sub-tree (None for the whole document)
+    pub field_path: Option<String>,
+    /// Text to look for
+    pub pattern: String,
+    /// How the pattern is compared
+    pub mode: MatchMode,
+    /// Which parts of the document are searched
+    pub scope: SearchScope,
+    /// Compare case-insensitively
+    pub ignore_case: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            format: None,
+            field_path: None,
+            pattern: String::new(),
+            mode: MatchMode::Substring,
+            scope: SearchScope::Both,
+            ignore_case: false,
+        }
+    }
+}
+
+/// A key or value that matched a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    /// Field path of the matching value, or of the value under a matching key
+    pub path: String,
+    /// The value at `path`
+    pub value: serde_json::Value,
+}
@@ -0,0 +1,206 @@
+//! CLI definitions and handling for find command
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use serde_json::{Map, Value as JsonValue};
+
+use super::core::search;
+use super::types::{MatchMode, SearchConfig, SearchMatch, SearchScope};
+use crate::batch::{expand_file_patterns, is_glob_pattern, process_files};
+use crate::error::{exit_code, ExitStatus};
+use crate::format::xcli::{format_arg, selected_format};
+
+/// Exit status when nothing matched (same as a missing field)
+const NO_MATCH_STATUS: i32 = 4;
+
+/// Define the find command CLI structure
+pub fn find_command() -> Command {
+    Command::new("find")
+        .about("Search keys and values and print the matching paths and values")
+        .visible_alias("grep")
+        .arg(
+            Arg::new("pattern")
+                .value_name("PATTERN")
+                .help("Text to search for (a substring unless --regex or --exact)")
+                .required(true),
+        )
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("File path or glob pattern ('-' for stdin, can be used multiple times)")
+                .action(clap::ArgAction::Append)
+                .default_value("package.json"),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help("Search only below this dot-separated path"),
+        )
+        .arg(format_arg())
+        .arg(
+            Arg::new("regex")
+                .short('e')
+                .long("regex")
+                .help("Treat PATTERN as a regular expression")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("exact")
+                .short('x')
+                .long("exact")
+                .help("Match only whole keys or values equal to PATTERN")
+                .conflicts_with("regex")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("ignore-case")
+                .short('i')
+                .long("ignore-case")
+                .help("Compare case-insensitively")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("keys")
+                .long("keys")
+                .help("Search object keys only")
+                .conflicts_with("values")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("values")
+                .long("values")
+                .help("Search scalar values only")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FORMAT")
+                .help("Output format: 'path: value' lines, or a {path: value} JSON object")
+                .value_parser(["lines", "json", "json-pretty"])
+                .default_value("lines"),
+        )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .help("Process multiple files with N worker threads (0 = number of CPUs)")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Suppress error messages (the exit status still reports failures)")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Handle find command logic
+///
+/// Exits with status 0 when something matched, 4 when nothing did, and the
+/// status of the first error otherwise.
+pub fn handle_find_command(matches: &ArgMatches) -> Result<()> {
+    let patterns: Vec<String> = matches
+        .get_many::<String>("file")
+        .unwrap()
+        .cloned()
+        .collect();
+    let quiet = matches.get_flag("quiet");
+    let config = SearchConfig {
+        file_path: String::new(),
+        format: selected_format(matches)?,
+        field_path: matches.get_one::<String>("field").cloned(),
+        pattern: matches.get_one::<String>("pattern").unwrap().to_string(),
+        mode: if matches.get_flag("regex") {
+            MatchMode::Regex
+        } else if matches.get_flag("exact") {
+            MatchMode::Exact
+        } else {
+            MatchMode::Substring
+        },
+        scope: if matches.get_flag("keys") {
+            SearchScope::Keys
+        } else if matches.get_flag("values") {
+            SearchScope::Values
+        } else {
+            SearchScope::Both
+        },
+        ignore_case: matches.get_flag("ignore-case"),
+    };
+
+    let files = expand_file_patterns(&patterns)?;
+    let prefixed = files.len() > 1 || patterns.iter().any(|p| is_glob_pattern(p));
+    let jobs = *matches.get_one::<usize>("jobs").unwrap();
+    let outcomes = process_files(&files, jobs, |file| {
+        search(&SearchConfig {
+            file_path: file.to_string(),
+            ..config.clone()
+        })
+    });
+
+    // Errors are printed here (unless --quiet) and only set the exit status
+    let mut failure = None;
+    let mut found = false;
+    let mut results = Map::new();
+    let output = matches.get_one::<String>("output").unwrap();
+    for outcome in outcomes {
+        let file_matches = match outcome.result {
+            Ok(file_matches) => file_matches,
+            Err(e) => {
+                if !quiet {
+                    eprintln!("Error: {}: {}", outcome.file, e);
+                }
+                failure.get_or_insert(exit_code(&e));
+                continue;
+            }
+        };
+        found |= !file_matches.is_empty();
+
+        if output == "lines" {
+            for SearchMatch { path, value } in file_matches {
+                if prefixed {
+                    println!("{}: {}: {}", outcome.file, path, value);
+                } else {
+                    println!("{}: {}", path, value);
+                }
+            }
+        } else {
+            let object: Map<String, JsonValue> = file_matches
+                .into_iter()
+                .map(|SearchMatch { path, value }| (path, value))
+                .collect();
+            results.insert(outcome.file, JsonValue::Object(object));
+        }
+    }
+
+    if output != "lines" {
+        // A single plain path prints its matches without the file level
+        let value = if prefixed {
+            JsonValue::Object(results)
+        } else {
+            results
+                .into_iter()
+                .next()
+                .map_or_else(|| JsonValue::Object(Map::new()), |(_, object)| object)
+        };
+        if output == "json-pretty" {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            println!("{}", value);
+        }
+    }
+
+    match failure {
+        Some(code) => Err(ExitStatus(code).into()),
+        None if !found => Err(ExitStatus(NO_MATCH_STATUS).into()),
+        None => Ok(()),
+    }
+}
@@ -1,47 +1,230 @@
 //! Core implementation for setting JSON fields
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde_json::{Map, Value as JsonValue};
-use std::fs;
 
-use super::types::SetConfig;
-use super::utils::{parse_value_with_type, split_field_path};
+use super::types::{NumericConfig, SetConfig, SortConfig, StringConfig, StringOp};
+use super::utils::{
+    apply_numeric_op, apply_string_op, check_conditions, get_nested_value_mut, parse_number,
+    parse_value_with_type, sort_array, sort_object_keys, split_field_path,
+};
 use crate::error::JsonExtractError;
+use crate::format::Format;
+use crate::path::{format_path, Segment};
+use crate::source::{read_source, write_output};
 
-/// Set a field in JSON file and return updated content
+/// Set a field in JSON file (`-` reads stdin) and return updated content
 pub fn set_field(config: &SetConfig) -> Result<String> {
-    // Read file content
-    let content = fs::read_to_string(&config.file_path)
-        .with_context(|| format!("Failed to read file: {}", config.file_path))?;
-
-    // Parse JSON
-    let mut json_value: JsonValue =
-        serde_json::from_str(&content).map_err(|e| JsonExtractError::InvalidJson {
-            file: config.file_path.clone(),
-            error: e.to_string(),
-        })?;
+    let content = read_source(&config.file_path)?;
+    set_field_from_str(&content, config)
+}
+
+/// Set a field in JSON text and return updated content
+///
+/// `config.file_path` names the source in error messages and selects the
+/// format when `config.format` is None.
+pub fn set_field_from_str(content: &str, config: &SetConfig) -> Result<String> {
+    let format = Format::resolve(config.format, &config.file_path);
+    let mut json_value = format.parse(content, &config.file_path)?;
+    set_field_in_value(&mut json_value, config)?;
+
+    // Convert back to text in the document's format
+    let updated_content = format.write(content, &json_value)?;
+    Ok(updated_content)
+}
+
+/// Set a field in a parsed document
+pub fn set_field_in_value(json_value: &mut JsonValue, config: &SetConfig) -> Result<()> {
+    check_conditions(json_value, &config.field_path, &config.conditions)?;
 
     // Split field path
     let parts = split_field_path(&config.field_path)?;
 
     // Set nested value
     set_nested_value(
-        &mut json_value,
+        json_value,
         &parts,
         config.value.as_str(),
         config.value_type.as_deref(),
         config.create_missing,
     )?;
+    Ok(())
+}
 
-    // Convert back to JSON string
-    let updated_content = serde_json::to_string_pretty(&json_value)?;
-    Ok(updated_content)
+/// Apply an arithmetic operation to a numeric field of a JSON file (`-` reads stdin)
+pub fn update_numeric_field(config: &NumericConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    update_numeric_field_from_str(&content, config)
+}
+
+/// Apply an arithmetic operation to a numeric field of JSON text and return updated content
+///
+/// When the field is missing and `default` is given, the field is created with
+/// the default value before the operation is applied.
+///
+/// `config.file_path` names the source in error messages and selects the
+/// format when `config.format` is None.
+pub fn update_numeric_field_from_str(content: &str, config: &NumericConfig) -> Result<String> {
+    let format = Format::resolve(config.format, &config.file_path);
+    let mut json_value = format.parse(content, &config.file_path)?;
+    update_numeric_field_in_value(&mut json_value, config)?;
+    Ok(format.write(content, &json_value)?)
+}
+
+/// Apply an arithmetic operation to a numeric field of a parsed document
+pub fn update_numeric_field_in_value(
+    json_value: &mut JsonValue,
+    config: &NumericConfig,
+) -> Result<()> {
+    check_conditions(json_value, &config.field_path, &config.conditions)?;
+    let parts = split_field_path(&config.field_path)?;
+    let operand = parse_number(&config.operand)?;
+
+    match get_nested_value_mut(json_value, &parts) {
+        Ok(_) => {}
+        Err(
+            JsonExtractError::FieldNotFound(_) | JsonExtractError::ArrayIndexOutOfBounds { .. },
+        ) if config.default.is_some() => {
+            let default = config.default.as_deref().unwrap();
+            let default = parse_number(default)?.to_string();
+            set_nested_value(json_value, &parts, &default, None, config.create_missing)?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let current = get_nested_value_mut(json_value, &parts)?;
+    *current = apply_numeric_op(current, config.operation, &operand).map_err(|e| match e {
+        JsonExtractError::InvalidValueType(msg) => {
+            JsonExtractError::InvalidValueType(format!("{}: {}", config.field_path, msg))
+        }
+        e => e,
+    })?;
+    Ok(())
+}
+
+/// Apply an arithmetic operation to a numeric field and save changes to file
+pub fn update_numeric_field_and_save(config: &NumericConfig) -> Result<()> {
+    let updated_content = update_numeric_field(config)?;
+    write_output(&config.file_path, &updated_content)
 }
 
-/// Recursively set nested value in JSON structure
+/// Apply a string transformation to a field of a JSON file (`-` reads stdin)
+pub fn transform_string_field(config: &StringConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    transform_string_field_from_str(&content, config)
+}
+
+/// Apply a string transformation to a field of JSON text and return updated content
+///
+/// Templates may create the field; every other operation requires an existing
+/// string value.
+///
+/// `config.file_path` names the source in error messages and selects the
+/// format when `config.format` is None.
+pub fn transform_string_field_from_str(content: &str, config: &StringConfig) -> Result<String> {
+    let format = Format::resolve(config.format, &config.file_path);
+    let mut json_value = format.parse(content, &config.file_path)?;
+    transform_string_field_in_value(&mut json_value, config)?;
+    Ok(format.write(content, &json_value)?)
+}
+
+/// Apply a string transformation to a field of a parsed document
+pub fn transform_string_field_in_value(
+    json_value: &mut JsonValue,
+    config: &StringConfig,
+) -> Result<()> {
+    check_conditions(json_value, &config.field_path, &config.conditions)?;
+    let parts = split_field_path(&config.field_path)?;
+
+    if let StringOp::Template(_) = config.operation {
+        let rendered = apply_string_op("", &config.operation, json_value)?;
+        set_nested_value(
+            json_value,
+            &parts,
+            &rendered,
+            Some("string"),
+            config.create_missing,
+        )?;
+    } else {
+        let current = get_nested_value_mut(json_value, &parts)?;
+        let text = current.as_str().ok_or_else(|| {
+            JsonExtractError::InvalidValueType(format!(
+                "{}: {} is not a string",
+                config.field_path, current
+            ))
+        })?;
+        let transformed = apply_string_op(text, &config.operation, &JsonValue::Null)?;
+        *current = JsonValue::String(transformed);
+    }
+    Ok(())
+}
+
+/// Apply a string transformation to a field and save changes to file
+pub fn transform_string_field_and_save(config: &StringConfig) -> Result<()> {
+    let updated_content = transform_string_field(config)?;
+    write_output(&config.file_path, &updated_content)
+}
+
+/// Sort an object's keys or an array's items in a JSON file (`-` reads stdin)
+pub fn sort_field(config: &SortConfig) -> Result<String> {
+    let content = read_source(&config.file_path)?;
+    sort_field_from_str(&content, config)
+}
+
+/// Sort an object's keys or an array's items in JSON text and return updated content
+///
+/// `config.file_path` names the source in error messages and selects the
+/// format when `config.format` is None.
+pub fn sort_field_from_str(content: &str, config: &SortConfig) -> Result<String> {
+    let format = Format::resolve(config.format, &config.file_path);
+    let mut json_value = format.parse(content, &config.file_path)?;
+    sort_field_in_value(&mut json_value, config)?;
+    Ok(format.write(content, &json_value)?)
+}
+
+/// Sort an object's keys or an array's items in a parsed document
+pub fn sort_field_in_value(json_value: &mut JsonValue, config: &SortConfig) -> Result<()> {
+    let target = match config.field_path.as_deref() {
+        Some(field_path) => get_nested_value_mut(json_value, &split_field_path(field_path)?)?,
+        None => json_value,
+    };
+
+    match target {
+        JsonValue::Object(_) => {
+            sort_object_keys(target, &config.key_order, config.recursive, config.reverse)
+        }
+        JsonValue::Array(_) => {
+            sort_array(target, config.by.as_deref(), config.reverse)?;
+            if config.recursive {
+                for item in target.as_array_mut().unwrap() {
+                    sort_object_keys(item, &config.key_order, true, config.reverse);
+                }
+            }
+        }
+        other => {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "{} is neither an object nor an array",
+                other
+            ))
+            .into())
+        }
+    }
+    Ok(())
+}
+
+/// Sort an object's keys or an array's items and save changes to file
+pub fn sort_field_and_save(config: &SortConfig) -> Result<()> {
+    let updated_content = sort_field(config)?;
+    write_output(&config.file_path, &updated_content)
+}
+
+/// Set nested value in JSON structure, creating missing parents
+///
+/// Missing keys are always created; `create_missing` also replaces non-object
+/// parents and pads arrays with nulls up to the index.
 fn set_nested_value(
     current: &mut JsonValue,
-    parts: &[String],
+    parts: &[Segment],
     value: &str,
     value_type: Option<&str>,
     create_missing: bool,
@@ -49,119 +232,80 @@ fn set_nested_value(
     if parts.is_empty() {
         return Err(JsonExtractError::FieldNotFound("Empty path".to_string()));
     }
+    let parsed_value = parse_value_with_type(value, value_type)?;
 
-    let (first, rest) = parts.split_first().unwrap();
-
-    // Handle array syntax (e.g., "arr[0]")
-    if first.contains('[') {
-        let bracket_start = first.find('[').ok_or_else(|| {
-            JsonExtractError::InvalidArrayIndex(format!("Invalid array syntax: {}", first))
-        })?;
-        let array_name = &first[..bracket_start];
-        let index_part = &first[bracket_start + 1..first.len() - 1];
-        let index: usize = index_part
-            .parse()
-            .map_err(|_| JsonExtractError::InvalidArrayIndex(index_part.to_string()))?;
-
-        // Ensure parent is an object
-        let current_obj = current.as_object_mut().ok_or_else(|| {
-            JsonExtractError::NotAnObject(format!("Parent of {} is not an object", array_name))
-        })?;
+    let mut current = current;
+    for i in 0..parts.len() {
+        // Value inserted for a missing key: a container for the next segment
+        let missing = match parts.get(i + 1) {
+            Some(Segment::Index(_)) => JsonValue::Array(Vec::new()),
+            Some(Segment::Key(_)) => JsonValue::Object(Map::new()),
+            None => JsonValue::Null,
+        };
+        current = step_mut(current, parts, i, missing, create_missing)?;
+    }
+    *current = parsed_value;
+    Ok(())
+}
 
-        // Get or create array
-        let array = current_obj
-            .entry(array_name)
-            .or_insert_with(|| JsonValue::Array(Vec::new()))
-            .as_array_mut()
-            .ok_or_else(|| JsonExtractError::NotAnArray(array_name.to_string()))?;
-
-        // Ensure array has enough elements if creating missing
-        if create_missing {
-            while array.len() <= index {
-                array.push(JsonValue::Null);
+/// Child of `current` for the segment `parts[i]`, created when missing
+fn step_mut<'a>(
+    current: &'a mut JsonValue,
+    parts: &[Segment],
+    i: usize,
+    missing: JsonValue,
+    create_missing: bool,
+) -> Result<&'a mut JsonValue, JsonExtractError> {
+    match &parts[i] {
+        Segment::Key(key) => {
+            if !current.is_object() {
+                if !create_missing {
+                    return Err(JsonExtractError::NotAnObject(format!(
+                        "Cannot set field {} on non-object value",
+                        key
+                    )));
+                }
+                *current = JsonValue::Object(Map::new());
             }
+            let object = current.as_object_mut().unwrap();
+            Ok(object.entry(key.clone()).or_insert(missing))
         }
+        Segment::Index(index) => {
+            if current.is_null() && create_missing {
+                *current = JsonValue::Array(Vec::new());
+            }
+            let array = current
+                .as_array_mut()
+                .ok_or_else(|| JsonExtractError::NotAnArray(format_path(&parts[..i])))?;
 
-        if rest.is_empty() {
-            let array_len = array.len();
-
-            // Set array element value
-            let parsed_value = parse_value_with_type(value, value_type)?;
+            // Ensure array has enough elements if creating missing
+            if create_missing {
+                while array.len() <= *index {
+                    array.push(JsonValue::Null);
+                }
+            }
+            let length = array.len();
             array
-                .get_mut(index)
+                .get_mut(*index)
                 .ok_or_else(|| JsonExtractError::ArrayIndexOutOfBounds {
-                    path: array_name.to_string(),
-                    index,
-                    length: array_len,
-                })?
-                .clone_from(&parsed_value);
-        } else {
-            let array_len = array.len();
-            // Recurse into nested structure
-            let elem =
-                array
-                    .get_mut(index)
-                    .ok_or_else(|| JsonExtractError::ArrayIndexOutOfBounds {
-                        path: array_name.to_string(),
-                        index,
-                        length: array_len,
-                    })?;
-            set_nested_value(elem, rest, value, value_type, create_missing)?;
-        }
-    } else {
-        // Handle regular fields
-        if rest.is_empty() {
-            // Set final field value
-            let parsed_value = parse_value_with_type(value, value_type)?;
-            if let JsonValue::Object(obj) = current {
-                obj.insert(first.clone(), parsed_value);
-            } else if create_missing {
-                // Create parent object if missing and allowed
-                *current = JsonValue::Object(Map::new());
-                current
-                    .as_object_mut()
-                    .unwrap()
-                    .insert(first.clone(), parsed_value);
-            } else {
-                return Err(JsonExtractError::NotAnObject(format!(
-                    "Cannot set field {} on non-object value",
-                    first
-                )));
-            }
-        } else {
-            // Recurse into child fields
-            let next = if let Some(obj) = current.as_object_mut() {
-                obj.entry(first.clone())
-                    .or_insert_with(|| JsonValue::Object(Map::new()))
-            } else if create_missing {
-                *current = JsonValue::Object(Map::new());
-                current
-                    .as_object_mut()
-                    .unwrap()
-                    .entry(first.clone())
-                    .or_insert_with(|| JsonValue::Object(Map::new()))
-            } else {
-                return Err(JsonExtractError::NotAnObject(first.clone()));
-            };
-
-            set_nested_value(next, rest, value, value_type, create_missing)?;
+                    path: format_path(&parts[..i]),
+                    index: *index,
+                    length,
+                })
         }
     }
-
-    Ok(())
 }
 
 /// Set field and save changes to file
 pub fn set_field_and_save(config: &SetConfig) -> Result<()> {
     let updated_content = set_field(config)?;
-    fs::write(&config.file_path, updated_content)
-        .with_context(|| format!("Failed to write to file: {}", config.file_path))?;
-    Ok(())
+    write_output(&config.file_path, &updated_content)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::set::types::NumericOp;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -177,6 +321,8 @@ mod tests {
             value: "new".to_string(),
             value_type: None,
             create_missing: false,
+            conditions: Vec::new(),
+            format: None,
         };
 
         let updated = set_field(&config).unwrap();
@@ -195,6 +341,8 @@ mod tests {
             value: "Charlie".to_string(),
             value_type: None,
             create_missing: false,
+            conditions: Vec::new(),
+            format: None,
         };
 
         let updated = set_field(&config).unwrap();
@@ -215,4 +363,139 @@ mod tests {
         assert_eq!(authors[0], "Charlie");
         assert_eq!(authors[1], "Bob");
     }
+
+    #[test]
+    fn test_update_numeric_field() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, r#"{{"build": {{"number": 41}}, "name": "x"}}"#).unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = NumericConfig {
+            file_path: path.to_string(),
+            field_path: "build.number".to_string(),
+            ..Default::default()
+        };
+        let parsed: JsonValue =
+            serde_json::from_str(&update_numeric_field(&config).unwrap()).unwrap();
+        assert_eq!(parsed["build"]["number"], 42);
+
+        let config = NumericConfig {
+            field_path: "name".to_string(),
+            ..config
+        };
+        let err = update_numeric_field(&config).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<JsonExtractError>(),
+            Some(JsonExtractError::InvalidValueType(_))
+        ));
+
+        let config = NumericConfig {
+            field_path: "build.retries".to_string(),
+            operation: NumericOp::Mul,
+            operand: "2".to_string(),
+            default: Some("5".to_string()),
+            ..config
+        };
+        let parsed: JsonValue =
+            serde_json::from_str(&update_numeric_field(&config).unwrap()).unwrap();
+        assert_eq!(parsed["build"]["retries"], 10);
+    }
+
+    #[test]
+    fn test_transform_string_field() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"name": "pkg", "version": "1.0.0", "n": 1}}"#
+        )
+        .unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = StringConfig {
+            file_path: path.to_string(),
+            field_path: "id".to_string(),
+            operation: StringOp::Template("${name}@${version}".to_string()),
+            ..Default::default()
+        };
+        let parsed: JsonValue =
+            serde_json::from_str(&transform_string_field(&config).unwrap()).unwrap();
+        assert_eq!(parsed["id"], "pkg@1.0.0");
+
+        let config = StringConfig {
+            field_path: "n".to_string(),
+            operation: StringOp::Case("upper".to_string()),
+            ..config
+        };
+        assert!(transform_string_field(&config).is_err());
+    }
+
+    #[test]
+    fn test_sort_field() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"deps": {{"b": "1", "a": "2"}}, "tags": ["y", "x"]}}"#
+        )
+        .unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let config = SortConfig {
+            file_path: path.to_string(),
+            field_path: Some("deps".to_string()),
+            ..Default::default()
+        };
+        let updated = sort_field(&config).unwrap();
+        assert!(updated.find("\"a\"").unwrap() < updated.find("\"b\"").unwrap());
+
+        let config = SortConfig {
+            field_path: Some("tags".to_string()),
+            ..config
+        };
+        let parsed: JsonValue = serde_json::from_str(&sort_field(&config).unwrap()).unwrap();
+        assert_eq!(parsed["tags"], serde_json::json!(["x", "y"]));
+
+        // --reverse also applies to the keys of objects inside a sorted array
+        let mut value = serde_json::json!([{"a": 1, "b": 2}, {"a": 3, "c": 4}]);
+        let config = SortConfig {
+            field_path: None,
+            by: Some("a".to_string()),
+            recursive: true,
+            reverse: true,
+            ..config
+        };
+        sort_field_in_value(&mut value, &config).unwrap();
+        let keys: Vec<Vec<&String>> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item.as_object().unwrap().keys().collect())
+            .collect();
+        assert_eq!(keys, [["c", "a"], ["b", "a"]]);
+    }
+
+    #[test]
+    fn test_set_field_jsonc() {
+        let content = "{\n  // build target\n  \"target\": \"es2020\",\n}\n";
+        let config = SetConfig {
+            file_path: "tsconfig.json".to_string(),
+            field_path: "target".to_string(),
+            value: "es2022".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            set_field_from_str(content, &config).unwrap(),
+            "{\n  // build target\n  \"target\": \"es2022\",\n}\n"
+        );
+
+        let config = SetConfig {
+            file_path: "-".to_string(),
+            ..config
+        };
+        assert!(set_field_from_str(content, &config).is_err());
+        let config = SetConfig {
+            format: Some(Format::Jsonc),
+            ..config
+        };
+        assert!(set_field_from_str(content, &config).is_ok());
+    }
 }
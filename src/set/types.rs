@@ -1,5 +1,7 @@
 //! Configuration types for set operations
 
+use crate::format::Format;
+
 /// Configuration for field setting
 #[derive(Debug, Clone)]
 pub struct SetConfig {
@@ -13,6 +15,10 @@ pub struct SetConfig {
     pub value_type: Option<String>,
     /// Whether to create missing parent fields
     pub create_missing: bool,
+    /// Guards that must all hold for the write to happen
+    pub conditions: Vec<SetCondition>,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
 }
 
 impl Default for SetConfig {
@@ -23,6 +29,163 @@ impl Default for SetConfig {
             value: "".to_string(),
             value_type: None,
             create_missing: false,
+            conditions: Vec::new(),
+            format: None,
+        }
+    }
+}
+
+/// Guard checked against the current value before a conditional update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetCondition {
+    /// Current value equals the given value (auto-detected like `--value`)
+    Equals(String),
+    /// Field does not exist yet
+    Missing,
+    /// Current value has the given type (object, array, string, number,
+    /// integer, float, boolean, null)
+    Type(String),
+}
+
+/// Arithmetic operation applied to a numeric field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericOp {
+    /// Add the operand to the current value
+    Incr,
+    /// Subtract the operand from the current value
+    Decr,
+    /// Multiply the current value by the operand
+    Mul,
+}
+
+/// Configuration for numeric field updates
+#[derive(Debug, Clone)]
+pub struct NumericConfig {
+    /// Path to the JSON file
+    pub file_path: String,
+    /// Dot-separated path to the numeric field
+    pub field_path: String,
+    /// Operation to apply
+    pub operation: NumericOp,
+    /// Operand of the operation (integer or float)
+    pub operand: String,
+    /// Initial value used when the field is missing (None to fail instead)
+    pub default: Option<String>,
+    /// Whether to create missing parent fields
+    pub create_missing: bool,
+    /// Guards that must all hold for the write to happen
+    pub conditions: Vec<SetCondition>,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
+}
+
+impl Default for NumericConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: "build.number".to_string(),
+            operation: NumericOp::Incr,
+            operand: "1".to_string(),
+            default: None,
+            create_missing: false,
+            conditions: Vec::new(),
+            format: None,
+        }
+    }
+}
+
+/// String transformation applied to a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StringOp {
+    /// Replace every match of a regular expression (`$1` refers to capture groups)
+    Replace {
+        /// Regular expression to search for
+        pattern: String,
+        /// Replacement text
+        replacement: String,
+    },
+    /// Prepend text
+    Prefix(String),
+    /// Append text
+    Suffix(String),
+    /// Convert case (upper, lower, capitalize, camel, snake, kebab)
+    Case(String),
+    /// Remove leading and trailing whitespace
+    Trim,
+    /// Render a template referencing other fields, e.g. `${name}@${version}`
+    Template(String),
+}
+
+/// Configuration for string field transformations
+#[derive(Debug, Clone)]
+pub struct StringConfig {
+    /// Path to the JSON file
+    pub file_path: String,
+    /// Dot-separated path to the string field
+    pub field_path: String,
+    /// Transformation to apply
+    pub operation: StringOp,
+    /// Whether to create missing parent fields (templates only)
+    pub create_missing: bool,
+    /// Guards that must all hold for the write to happen
+    pub conditions: Vec<SetCondition>,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
+}
+
+impl Default for StringConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: "name".to_string(),
+            operation: StringOp::Trim,
+            create_missing: false,
+            conditions: Vec::new(),
+            format: None,
+        }
+    }
+}
+
+/// Order used when sorting object keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Plain alphabetical order
+    Alphabetical,
+    /// Conventional package.json order (name, version, description, ...)
+    PackageJson,
+    /// Listed keys first in the given order, remaining keys alphabetically
+    Custom(Vec<String>),
+}
+
+/// Configuration for sorting objects and arrays
+#[derive(Debug, Clone)]
+pub struct SortConfig {
+    /// Path to the JSON file
+    pub file_path: String,
+    /// Dot-separated path to the value to sort (None for the whole document)
+    pub field_path: Option<String>,
+    /// Order used for object keys
+    pub key_order: KeyOrder,
+    /// Whether to sort keys of nested objects as well (always alphabetically)
+    pub recursive: bool,
+    /// Key path used to sort arrays of objects
+    pub by: Option<String>,
+    /// Whether to reverse the resulting order
+    pub reverse: bool,
+    /// Document format (None to detect from the file name)
+    pub format: Option<Format>,
+}
+
+impl Default for SortConfig {
+    fn default() -> Self {
+        Self {
+            file_path: "package.json".to_string(),
+            field_path: None,
+            key_order: KeyOrder::Alphabetical,
+            recursive: false,
+            by: None,
+            reverse: false,
+            format: None,
         }
     }
 }
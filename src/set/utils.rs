@@ -1,48 +1,15 @@
 //! Utility functions for set operations
 
+use super::types::{KeyOrder, NumericOp, SetCondition, StringOp};
 use crate::error::JsonExtractError;
-use serde_json::Value as JsonValue;
-
-/// Split field path into segments (handles array syntax like "arr\[0\]")
-pub fn split_field_path(field_path: &str) -> Result<Vec<String>, JsonExtractError> {
-    let mut parts = Vec::new();
-    let mut current = String::new();
-    let mut in_array = false;
-
-    for c in field_path.chars() {
-        match c {
-            '.' if !in_array => {
-                if current.is_empty() {
-                    return Err(JsonExtractError::InvalidFieldPath(
-                        "Empty path segment".to_string(),
-                    ));
-                }
-                parts.push(current);
-                current = String::new();
-            }
-            '[' => {
-                in_array = true;
-                current.push(c);
-            }
-            ']' => {
-                in_array = false;
-                current.push(c);
-            }
-            _ => {
-                current.push(c);
-            }
-        }
-    }
-
-    if !current.is_empty() {
-        parts.push(current);
-    } else {
-        return Err(JsonExtractError::InvalidFieldPath(
-            "Field path cannot end with a dot".to_string(),
-        ));
-    }
+use crate::get::utils::{get_nested_value, value_type_name};
+use crate::path::{format_path, parse_path, Segment};
+use serde_json::{Map, Value as JsonValue};
+use std::cmp::Ordering;
 
-    Ok(parts)
+/// Split field path into keys and array indexes (see [`crate::path`])
+pub fn split_field_path(field_path: &str) -> Result<Vec<Segment>, JsonExtractError> {
+    parse_path(field_path)
 }
 
 /// Parse value with optional type hint
@@ -96,24 +63,464 @@ pub fn parse_value_with_type(
     }
 }
 
+/// Resolve a mutable reference to an existing value from split path segments
+pub fn get_nested_value_mut<'a>(
+    value: &'a mut JsonValue,
+    parts: &[Segment],
+) -> Result<&'a mut JsonValue, JsonExtractError> {
+    let mut current = value;
+
+    for (i, part) in parts.iter().enumerate() {
+        current = match part {
+            Segment::Key(key) => current
+                .get_mut(key)
+                .ok_or_else(|| JsonExtractError::FieldNotFound(key.clone()))?,
+            Segment::Index(index) => {
+                let array = current
+                    .as_array_mut()
+                    .ok_or_else(|| JsonExtractError::NotAnArray(format_path(&parts[..i])))?;
+                let length = array.len();
+                array
+                    .get_mut(*index)
+                    .ok_or_else(|| JsonExtractError::ArrayIndexOutOfBounds {
+                        path: format_path(&parts[..i]),
+                        index: *index,
+                        length,
+                    })?
+            }
+        };
+    }
+
+    Ok(current)
+}
+
+/// Check conditional-update guards against the current value at `field_path`
+pub fn check_conditions(
+    value: &JsonValue,
+    field_path: &str,
+    conditions: &[SetCondition],
+) -> Result<(), JsonExtractError> {
+    let current = match get_nested_value(value, field_path) {
+        Ok(current) => Some(current),
+        Err(
+            JsonExtractError::FieldNotFound(_) | JsonExtractError::ArrayIndexOutOfBounds { .. },
+        ) => None,
+        Err(e) => return Err(e),
+    };
+
+    for condition in conditions {
+        let failure = match (condition, current) {
+            (SetCondition::Missing, Some(current)) => Some(format!(
+                "{} already exists with value {}",
+                field_path, current
+            )),
+            (SetCondition::Missing, None) => None,
+            (_, None) => Some(format!("{} does not exist", field_path)),
+            (SetCondition::Equals(expected), Some(current)) => {
+                let expected = parse_value_with_type(expected, None)?;
+                (*current != expected)
+                    .then(|| format!("{} is {}, expected {}", field_path, current, expected))
+            }
+            (SetCondition::Type(expected), Some(current)) => {
+                let matches = match expected.as_str() {
+                    "integer" => current.is_i64() || current.is_u64(),
+                    "float" => current.is_f64(),
+                    name => value_type_name(current) == name,
+                };
+                (!matches).then(|| {
+                    format!(
+                        "{} is of type {}, expected {}",
+                        field_path,
+                        value_type_name(current),
+                        expected
+                    )
+                })
+            }
+        };
+
+        if let Some(message) = failure {
+            return Err(JsonExtractError::ConditionNotMet(message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a numeric operand, rejecting anything that is not an integer or float
+pub fn parse_number(value: &str) -> Result<JsonValue, JsonExtractError> {
+    match parse_value_with_type(value, None)? {
+        JsonValue::Number(n) => Ok(JsonValue::Number(n)),
+        _ => Err(JsonExtractError::InvalidValueType(format!(
+            "{} is not a valid number",
+            value
+        ))),
+    }
+}
+
+/// Apply an arithmetic operation to a numeric value
+///
+/// Integer operands on integer values stay integers (with overflow checking);
+/// any float involved turns the result into a float.
+pub fn apply_numeric_op(
+    current: &JsonValue,
+    op: NumericOp,
+    operand: &JsonValue,
+) -> Result<JsonValue, JsonExtractError> {
+    let (lhs, rhs) = match (current, operand) {
+        (JsonValue::Number(lhs), JsonValue::Number(rhs)) => (lhs, rhs),
+        (JsonValue::Number(_), _) => {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "{} is not a valid number",
+                operand
+            )))
+        }
+        _ => {
+            return Err(JsonExtractError::InvalidValueType(format!(
+                "{} is not a number",
+                current
+            )))
+        }
+    };
+
+    if let (Some(a), Some(b)) = (lhs.as_i64(), rhs.as_i64()) {
+        let result = match op {
+            NumericOp::Incr => a.checked_add(b),
+            NumericOp::Decr => a.checked_sub(b),
+            NumericOp::Mul => a.checked_mul(b),
+        };
+        return result.map(JsonValue::from).ok_or_else(|| {
+            JsonExtractError::InvalidValueType(format!("Integer overflow applying {:?}", op))
+        });
+    }
+
+    let (a, b) = (lhs.as_f64().unwrap(), rhs.as_f64().unwrap());
+    let result = match op {
+        NumericOp::Incr => a + b,
+        NumericOp::Decr => a - b,
+        NumericOp::Mul => a * b,
+    };
+    serde_json::Number::from_f64(result)
+        .map(JsonValue::Number)
+        .ok_or_else(|| {
+            JsonExtractError::InvalidValueType(format!("{} is not a finite number", result))
+        })
+}
+
+/// Apply a string transformation to the current value of a field
+///
+/// Templates are rendered against `document`; every other operation
+/// transforms `current`.
+pub fn apply_string_op(
+    current: &str,
+    op: &StringOp,
+    document: &JsonValue,
+) -> Result<String, JsonExtractError> {
+    match op {
+        StringOp::Replace {
+            pattern,
+            replacement,
+        } => {
+            let regex = regex::Regex::new(pattern)
+                .map_err(|e| JsonExtractError::InvalidPattern(format!("{}: {}", pattern, e)))?;
+            Ok(regex
+                .replace_all(current, replacement.as_str())
+                .into_owned())
+        }
+        StringOp::Prefix(prefix) => Ok(format!("{}{}", prefix, current)),
+        StringOp::Suffix(suffix) => Ok(format!("{}{}", current, suffix)),
+        StringOp::Case(style) => convert_case(current, style),
+        StringOp::Trim => Ok(current.trim().to_string()),
+        StringOp::Template(template) => render_template(template, document),
+    }
+}
+
+/// Convert a string to the given case style
+///
+/// Supported styles: upper, lower, capitalize, camel, snake, kebab. Word
+/// boundaries are non-alphanumeric characters and lower-to-upper transitions.
+pub fn convert_case(value: &str, style: &str) -> Result<String, JsonExtractError> {
+    let words = || {
+        let mut words: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in value.chars() {
+            if !c.is_alphanumeric() {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                prev_lower = false;
+                continue;
+            }
+            if c.is_uppercase() && prev_lower {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.extend(c.to_lowercase());
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+        words
+    };
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    match style {
+        "upper" => Ok(value.to_uppercase()),
+        "lower" => Ok(value.to_lowercase()),
+        "capitalize" => Ok(capitalize(value)),
+        "snake" => Ok(words().join("_")),
+        "kebab" => Ok(words().join("-")),
+        "camel" => Ok(words()
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                if i == 0 {
+                    word.clone()
+                } else {
+                    capitalize(word)
+                }
+            })
+            .collect()),
+        _ => Err(JsonExtractError::InvalidValueType(format!(
+            "Unknown case style: {}",
+            style
+        ))),
+    }
+}
+
+/// Render `${field.path}` placeholders from the values of a JSON document
+///
+/// Strings are inserted without quotes, other values as JSON; `$$` produces a
+/// literal `$`.
+pub fn render_template(template: &str, document: &JsonValue) -> Result<String, JsonExtractError> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            output.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after.find('}').ok_or_else(|| {
+                JsonExtractError::InvalidFieldPath(format!("Unclosed placeholder in: {}", template))
+            })?;
+            let value = get_nested_value(document, after[..end].trim())?;
+            match value {
+                JsonValue::String(s) => output.push_str(s),
+                other => output.push_str(&other.to_string()),
+            }
+            rest = &after[end + 1..];
+        } else {
+            output.push('$');
+            rest = &rest[1..];
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Conventional key order of package.json files (as used by sort-package-json)
+pub const PACKAGE_JSON_KEY_ORDER: &[&str] = &[
+    "$schema",
+    "name",
+    "displayName",
+    "version",
+    "private",
+    "description",
+    "categories",
+    "keywords",
+    "homepage",
+    "bugs",
+    "repository",
+    "funding",
+    "license",
+    "author",
+    "maintainers",
+    "contributors",
+    "publisher",
+    "sideEffects",
+    "type",
+    "imports",
+    "exports",
+    "main",
+    "umd:main",
+    "jsdelivr",
+    "unpkg",
+    "module",
+    "source",
+    "browser",
+    "react-native",
+    "types",
+    "typesVersions",
+    "typings",
+    "style",
+    "bin",
+    "man",
+    "directories",
+    "files",
+    "workspaces",
+    "scripts",
+    "husky",
+    "lint-staged",
+    "config",
+    "babel",
+    "browserslist",
+    "prettier",
+    "eslintConfig",
+    "jest",
+    "overrides",
+    "resolutions",
+    "dependencies",
+    "devDependencies",
+    "dependenciesMeta",
+    "peerDependencies",
+    "peerDependenciesMeta",
+    "optionalDependencies",
+    "bundledDependencies",
+    "bundleDependencies",
+    "packageManager",
+    "engines",
+    "volta",
+    "os",
+    "cpu",
+    "publishConfig",
+];
+
+/// Sort the keys of an object, recursing into nested values if requested
+///
+/// Nested objects are always sorted alphabetically; arrays keep their order
+/// but objects inside them are sorted as well.
+pub fn sort_object_keys(value: &mut JsonValue, order: &KeyOrder, recursive: bool, reverse: bool) {
+    if recursive {
+        let children: Box<dyn Iterator<Item = &mut JsonValue>> = match value {
+            JsonValue::Object(map) => Box::new(map.values_mut()),
+            JsonValue::Array(items) => Box::new(items.iter_mut()),
+            _ => return,
+        };
+        for child in children {
+            sort_object_keys(child, &KeyOrder::Alphabetical, true, reverse);
+        }
+    }
+
+    let JsonValue::Object(map) = value else {
+        return;
+    };
+
+    let rank = |key: &str| -> usize {
+        match order {
+            KeyOrder::Alphabetical => 0,
+            KeyOrder::PackageJson => PACKAGE_JSON_KEY_ORDER
+                .iter()
+                .position(|k| *k == key)
+                .unwrap_or(usize::MAX),
+            KeyOrder::Custom(keys) => keys.iter().position(|k| k == key).unwrap_or(usize::MAX),
+        }
+    };
+
+    let mut entries: Vec<(String, JsonValue)> = std::mem::take(map).into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    if reverse {
+        entries.reverse();
+    }
+    *map = entries.into_iter().collect::<Map<String, JsonValue>>();
+}
+
+/// Sort an array of scalars, or of objects by the value at key path `by`
+pub fn sort_array(
+    value: &mut JsonValue,
+    by: Option<&str>,
+    reverse: bool,
+) -> Result<(), JsonExtractError> {
+    let JsonValue::Array(items) = value else {
+        return Err(JsonExtractError::NotAnArray(format!(
+            "cannot sort {} value",
+            value_type_name(value)
+        )));
+    };
+
+    match by {
+        Some(path) => {
+            let mut keyed = Vec::with_capacity(items.len());
+            for item in items.drain(..) {
+                let key = get_nested_value(&item, path)?.clone();
+                keyed.push((key, item));
+            }
+            keyed.sort_by(|(a, _), (b, _)| compare_values(a, b));
+            items.extend(keyed.into_iter().map(|(_, item)| item));
+        }
+        None => {
+            if let Some(object) = items
+                .iter()
+                .find(|item| item.is_object() || item.is_array())
+            {
+                return Err(JsonExtractError::InvalidValueType(format!(
+                    "Cannot sort array containing {} values without a sort key",
+                    value_type_name(object)
+                )));
+            }
+            items.sort_by(compare_values);
+        }
+    }
+
+    if reverse {
+        items.reverse();
+    }
+    Ok(())
+}
+
+/// Total order over JSON values: null < boolean < number < string < array < object
+pub fn compare_values(a: &JsonValue, b: &JsonValue) -> Ordering {
+    let rank = |v: &JsonValue| match v {
+        JsonValue::Null => 0,
+        JsonValue::Bool(_) => 1,
+        JsonValue::Number(_) => 2,
+        JsonValue::String(_) => 3,
+        JsonValue::Array(_) => 4,
+        JsonValue::Object(_) => 5,
+    };
+
+    match (a, b) {
+        (JsonValue::Bool(a), JsonValue::Bool(b)) => a.cmp(b),
+        (JsonValue::Number(a), JsonValue::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (JsonValue::String(a), JsonValue::String(b)) => a.cmp(b),
+        _ => rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a.to_string().cmp(&b.to_string())),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_split_field_path() {
+        let key = |key: &str| Segment::Key(key.to_string());
         assert_eq!(
             split_field_path("package.name").unwrap(),
-            vec!["package".to_string(), "name".to_string()]
+            vec![key("package"), key("name")]
         );
 
         assert_eq!(
             split_field_path("dependencies.serde.version").unwrap(),
-            vec![
-                "dependencies".to_string(),
-                "serde".to_string(),
-                "version".to_string()
-            ]
+            vec![key("dependencies"), key("serde"), key("version")]
+        );
+        assert_eq!(
+            split_field_path("authors[0].name").unwrap(),
+            vec![key("authors"), Segment::Index(0), key("name")]
         );
     }
 
@@ -131,4 +538,97 @@ mod tests {
             matches!(parse_value_with_type("text", Some("string")).unwrap(), JsonValue::String(s) if s == "text")
         );
     }
+
+    #[test]
+    fn test_apply_numeric_op() {
+        use serde_json::json;
+
+        assert_eq!(
+            apply_numeric_op(&json!(41), NumericOp::Incr, &json!(1)).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            apply_numeric_op(&json!(1.5), NumericOp::Mul, &json!(2)).unwrap(),
+            json!(3.0)
+        );
+        assert!(matches!(
+            apply_numeric_op(&json!("7"), NumericOp::Decr, &json!(1)),
+            Err(JsonExtractError::InvalidValueType(_))
+        ));
+        assert!(apply_numeric_op(&json!(i64::MAX), NumericOp::Incr, &json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_check_conditions() {
+        use serde_json::json;
+
+        let value = json!({"version": "1.0.0", "count": 3});
+        let equals = |v: &str| vec![SetCondition::Equals(v.to_string())];
+
+        assert!(check_conditions(&value, "version", &equals("1.0.0")).is_ok());
+        assert!(matches!(
+            check_conditions(&value, "version", &equals("2.0.0")),
+            Err(JsonExtractError::ConditionNotMet(_))
+        ));
+        assert!(check_conditions(&value, "missing", &[SetCondition::Missing]).is_ok());
+        assert!(check_conditions(&value, "count", &[SetCondition::Missing]).is_err());
+        assert!(check_conditions(&value, "count", &[SetCondition::Type("integer".into())]).is_ok());
+        assert!(check_conditions(&value, "count", &[SetCondition::Type("string".into())]).is_err());
+    }
+
+    #[test]
+    fn test_apply_string_op() {
+        use serde_json::json;
+
+        let document = json!({"name": "pkg", "version": "1.0.0", "n": 2});
+        let apply = |current, op| apply_string_op(current, &op, &document).unwrap();
+
+        assert_eq!(
+            apply(
+                "git@github.com:org/repo.git",
+                StringOp::Replace {
+                    pattern: r"^git@([^:]+):(.*)$".to_string(),
+                    replacement: "https://$1/$2".to_string(),
+                }
+            ),
+            "https://github.com/org/repo.git"
+        );
+        assert_eq!(apply("build", StringOp::Prefix("pre".into())), "prebuild");
+        assert_eq!(apply("  x ", StringOp::Trim), "x");
+        assert_eq!(
+            apply("myHTTPServer v2", StringOp::Case("kebab".into())),
+            "my-httpserver-v2"
+        );
+        assert_eq!(
+            apply("foo_bar-baz", StringOp::Case("camel".into())),
+            "fooBarBaz"
+        );
+        assert_eq!(
+            apply("", StringOp::Template("${name}@${version} ($$${n})".into())),
+            "pkg@1.0.0 ($2)"
+        );
+        assert!(apply_string_op("", &StringOp::Template("${nope}".into()), &document).is_err());
+    }
+
+    #[test]
+    fn test_sort_object_keys_and_arrays() {
+        use serde_json::json;
+
+        let mut value =
+            json!({"scripts": {"b": 1, "a": 2}, "version": "1.0.0", "name": "x", "zzz": 0});
+        sort_object_keys(&mut value, &KeyOrder::PackageJson, true, false);
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"x","version":"1.0.0","scripts":{"a":2,"b":1},"zzz":0}"#
+        );
+
+        let mut value = json!([{"n": "b"}, {"n": "a"}]);
+        sort_array(&mut value, Some("n"), false).unwrap();
+        assert_eq!(value, json!([{"n": "a"}, {"n": "b"}]));
+
+        let mut value = json!([10, 9, "a", null]);
+        sort_array(&mut value, None, true).unwrap();
+        assert_eq!(value, json!(["a", 10, 9, null]));
+        assert!(sort_array(&mut json!([{}]), None, false).is_err());
+    }
 }
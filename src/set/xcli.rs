@@ -1,9 +1,27 @@
 use crate::{
-    set::core::{set_field, set_field_and_save},
+    batch::{expand_file_patterns, is_glob_pattern, process_files},
+    error::{exit_code, ExitStatus},
+    format::{
+        xcli::{format_arg, selected_format},
+        Format,
+    },
+    ndjson,
+    set::core::{
+        set_field_in_value, sort_field, sort_field_and_save, transform_string_field_in_value,
+        update_numeric_field_in_value,
+    },
+    set::types::{
+        KeyOrder, NumericConfig, NumericOp, SetCondition, SortConfig, StringConfig, StringOp,
+    },
+    source::{read_source, write_output, STDIO_PATH},
     SetConfig,
 };
-use anyhow::{Context, Result};
-use clap::{Arg, Command};
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgGroup, Command};
+use serde_json::Value as JsonValue;
+
+/// Edit applied to one parsed document (or NDJSON record)
+type ValueEdit = Box<dyn Fn(&mut JsonValue) -> Result<()> + Sync>;
 
 /// Define set command CLI structure
 pub fn cli() -> Command {
@@ -14,7 +32,8 @@ pub fn cli() -> Command {
                 .short('f')
                 .long("file")
                 .value_name("FILE")
-                .help("JSON file path")
+                .help("JSON file path or glob pattern ('-' for stdin, can be used multiple times)")
+                .action(clap::ArgAction::Append)
                 .default_value("package.json"),
         )
         .arg(
@@ -30,9 +49,94 @@ pub fn cli() -> Command {
                 .short('v')
                 .long("value")
                 .value_name("VALUE")
-                .help("Value to set for the field")
+                .help("Value to set for the field"),
+        )
+        .arg(
+            Arg::new("incr")
+                .long("incr")
+                .value_name("NUMBER")
+                .help("Increment a numeric field by NUMBER")
+                .allow_negative_numbers(true),
+        )
+        .arg(
+            Arg::new("decr")
+                .long("decr")
+                .value_name("NUMBER")
+                .help("Decrement a numeric field by NUMBER")
+                .allow_negative_numbers(true),
+        )
+        .arg(
+            Arg::new("mul")
+                .long("mul")
+                .value_name("NUMBER")
+                .help("Multiply a numeric field by NUMBER")
+                .allow_negative_numbers(true),
+        )
+        .arg(
+            Arg::new("default")
+                .long("default")
+                .value_name("NUMBER")
+                .help("Initial value for a missing field in --incr/--decr/--mul")
+                .requires("arithmetic")
+                .allow_negative_numbers(true),
+        )
+        .arg(
+            Arg::new("replace")
+                .long("replace")
+                .value_name("REGEX")
+                .help("Replace matches of REGEX in a string field (use with --with)")
+                .requires("with"),
+        )
+        .arg(
+            Arg::new("with")
+                .long("with")
+                .value_name("REPLACEMENT")
+                .help("Replacement for --replace ($1, $2... refer to capture groups)")
+                .allow_hyphen_values(true)
+                .requires("replace"),
+        )
+        .arg(
+            Arg::new("prefix")
+                .long("prefix")
+                .value_name("TEXT")
+                .help("Prepend TEXT to a string field")
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::new("suffix")
+                .long("suffix")
+                .value_name("TEXT")
+                .help("Append TEXT to a string field")
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::new("case")
+                .long("case")
+                .value_name("STYLE")
+                .help("Convert the case of a string field")
+                .value_parser(["upper", "lower", "capitalize", "camel", "snake", "kebab"]),
+        )
+        .arg(
+            Arg::new("trim")
+                .long("trim")
+                .help("Trim surrounding whitespace from a string field")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("template")
+                .long("template")
+                .value_name("TEMPLATE")
+                .help("Set the field from a template referencing other fields (e.g., '${name}@${version}')"),
+        )
+        .group(
+            ArgGroup::new("operation")
+                .args([
+                    "value", "incr", "decr", "mul", "replace", "prefix", "suffix", "case", "trim",
+                    "template",
+                ])
                 .required(true),
         )
+        .group(ArgGroup::new("arithmetic").args(["incr", "decr", "mul"]))
         .arg(
             Arg::new("type")
                 .short('t')
@@ -41,12 +145,43 @@ pub fn cli() -> Command {
                 .help("Value type (string, integer, float, boolean, null, auto)")
                 .default_value("auto"),
         )
+        .arg(
+            Arg::new("if-equals")
+                .long("if-equals")
+                .value_name("VALUE")
+                .help("Only write if the current value equals VALUE (exit code 3 otherwise)")
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::new("if-missing")
+                .long("if-missing")
+                .help("Only write if the field does not exist yet (exit code 3 otherwise)")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["if-equals", "if-type"]),
+        )
+        .arg(
+            Arg::new("if-type")
+                .long("if-type")
+                .value_name("TYPE")
+                .help("Only write if the current value has TYPE (exit code 3 otherwise)")
+                .value_parser([
+                    "object", "array", "string", "number", "integer", "float", "boolean", "null",
+                ]),
+        )
         .arg(
             Arg::new("create-missing")
                 .long("create-missing")
                 .help("Create missing parent fields if they don't exist")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(format_arg())
+        .arg(
+            Arg::new("jsonc")
+                .long("jsonc")
+                .help("Parse input as JSONC and keep comments when writing; auto-detected for tsconfig.json etc.")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("format"),
+        )
         .arg(
             Arg::new("in-place")
                 .short('i')
@@ -54,53 +189,353 @@ pub fn cli() -> Command {
                 .help("Modify the file in place")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("jobs")
+                .short('j')
+                .long("jobs")
+                .value_name("N")
+                .help("Process multiple files with N worker threads (0 = number of CPUs)")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1"),
+        )
+        .arg(
+            Arg::new("output-file")
+                .long("output-file")
+                .value_name("FILE")
+                .help("Write the modified document to FILE instead of stdout")
+                .conflicts_with("in-place"),
+        )
+        .arg(
+            Arg::new("ndjson")
+                .long("ndjson")
+                .help("Treat input as JSON Lines and edit every record, streaming line by line")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["format", "jsonc"]),
+        )
 }
 
 /// Handle set command logic
 pub fn handle_set_command(matches: &clap::ArgMatches) -> Result<()> {
-    let file_path = matches
-        .get_one::<String>("file")
-        .context("File path is required")?;
+    let patterns: Vec<String> = matches
+        .get_many::<String>("file")
+        .context("File path is required")?
+        .cloned()
+        .collect();
+    let file_path = &patterns[0];
     let field_path = matches
         .get_one::<String>("field")
         .context("Field path is required")?;
-    let value = matches
-        .get_one::<String>("value")
-        .context("Value is required")?;
-    let value_type = matches
-        .get_one::<String>("type")
-        .context("Value type is required")?;
     let create_missing = matches.get_flag("create-missing");
     let in_place = matches.get_flag("in-place");
+    let format = selected_format(matches)?;
+
+    // Collect conditional-update guards
+    let mut conditions = Vec::new();
+    if let Some(expected) = matches.get_one::<String>("if-equals") {
+        conditions.push(SetCondition::Equals(expected.to_string()));
+    }
+    if matches.get_flag("if-missing") {
+        conditions.push(SetCondition::Missing);
+    }
+    if let Some(expected) = matches.get_one::<String>("if-type") {
+        conditions.push(SetCondition::Type(expected.to_string()));
+    }
+
+    // Numeric operations
+    let numeric_op = [
+        ("incr", NumericOp::Incr, "incremented by"),
+        ("decr", NumericOp::Decr, "decremented by"),
+        ("mul", NumericOp::Mul, "multiplied by"),
+    ]
+    .into_iter()
+    .find_map(|(id, op, verb)| {
+        matches
+            .get_one::<String>(id)
+            .map(|operand| (op, verb, operand))
+    });
+
+    // String transformations
+    let string_op = if let Some(pattern) = matches.get_one::<String>("replace") {
+        let replacement = matches.get_one::<String>("with").unwrap();
+        Some(StringOp::Replace {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+        })
+    } else if let Some(prefix) = matches.get_one::<String>("prefix") {
+        Some(StringOp::Prefix(prefix.to_string()))
+    } else if let Some(suffix) = matches.get_one::<String>("suffix") {
+        Some(StringOp::Suffix(suffix.to_string()))
+    } else if let Some(style) = matches.get_one::<String>("case") {
+        Some(StringOp::Case(style.to_string()))
+    } else if matches.get_flag("trim") {
+        Some(StringOp::Trim)
+    } else {
+        matches
+            .get_one::<String>("template")
+            .map(|template| StringOp::Template(template.to_string()))
+    };
+
+    // Build the edit applied to each document
+    let (edit, summary) = if let Some((operation, verb, operand)) = numeric_op {
+        let config = NumericConfig {
+            file_path: file_path.to_string(),
+            field_path: field_path.to_string(),
+            operation,
+            operand: operand.to_string(),
+            default: matches.get_one::<String>("default").cloned(),
+            create_missing,
+            conditions,
+            format,
+        };
+        let edit: ValueEdit = Box::new(move |value| update_numeric_field_in_value(value, &config));
+        (edit, format!("Field '{}' {} {}", field_path, verb, operand))
+    } else if let Some(operation) = string_op {
+        let config = StringConfig {
+            file_path: file_path.to_string(),
+            field_path: field_path.to_string(),
+            operation,
+            create_missing,
+            conditions,
+            format,
+        };
+        let edit: ValueEdit =
+            Box::new(move |value| transform_string_field_in_value(value, &config));
+        (edit, format!("Field '{}' transformed", field_path))
+    } else {
+        let value = matches
+            .get_one::<String>("value")
+            .context("Value is required")?;
+        let value_type = matches
+            .get_one::<String>("type")
+            .context("Value type is required")?;
+
+        // Handle value type (auto-detect or specified type)
+        let value_type = if value_type == "auto" {
+            None
+        } else {
+            Some(value_type.as_str())
+        };
+
+        // Build configuration
+        let config = SetConfig {
+            file_path: file_path.to_string(),
+            field_path: field_path.to_string(),
+            value: value.to_string(),
+            value_type: value_type.map(|s| s.to_string()),
+            create_missing,
+            conditions,
+            format,
+        };
+        let edit: ValueEdit = Box::new(move |value| set_field_in_value(value, &config));
+        (edit, format!("Field '{}' set to '{}'", field_path, value))
+    };
+
+    // Parse a whole file, apply the edit and render it back in the same format
+    let apply = |path: &str| -> Result<String> {
+        let content = read_source(path)?;
+        let format = Format::resolve(format, path);
+        let mut value = format.parse(&content, path)?;
+        edit(&mut value)?;
+        Ok(format.write(&content, &value)?)
+    };
+
+    let files = expand_file_patterns(&patterns)?;
+
+    // JSON Lines: stream every file record by record
+    if matches.get_flag("ndjson") {
+        let output_file = matches.get_one::<String>("output-file");
+        if output_file.is_some() && files.len() > 1 {
+            bail!("--output-file cannot be used with multiple input files");
+        }
+        for file in &files {
+            let destination = match output_file {
+                _ if in_place => file.as_str(),
+                Some(path) => path.as_str(),
+                None => STDIO_PATH,
+            };
+            let result = ndjson::rewrite_path(file, destination, &edit)?;
+            if destination != STDIO_PATH {
+                println!(
+                    "✅ {} in {} ({} record(s) updated, {} skipped)",
+                    summary, destination, result.updated, result.skipped
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    // A single plain path: write in place, to another file, or print modified content
+    if files.len() == 1 && !patterns.iter().any(|p| is_glob_pattern(p)) {
+        let updated = apply(&files[0])?;
+        let destination = if in_place {
+            Some(&files[0])
+        } else {
+            matches.get_one::<String>("output-file")
+        };
+        match destination {
+            Some(path) if path != STDIO_PATH => {
+                write_output(path, &updated)?;
+                println!("✅ {} in {}", summary, path);
+            }
+            _ => println!("{}", updated.strip_suffix('\n').unwrap_or(&updated)),
+        }
+        return Ok(());
+    }
+
+    if matches.contains_id("output-file") {
+        bail!("--output-file cannot be used with multiple input files");
+    }
+
+    // Multiple files: edit each one and report per-file results
+    let jobs = *matches.get_one::<usize>("jobs").unwrap();
+    let outcomes = process_files(&files, jobs, |path| {
+        let updated = apply(path)?;
+        if in_place {
+            write_output(path, &updated)?;
+        }
+        Ok(updated)
+    });
+
+    let mut codes = Vec::new();
+    for outcome in &outcomes {
+        match &outcome.result {
+            Ok(_) if in_place => println!("✅ {} in {}", summary, outcome.file),
+            Ok(updated) => println!(
+                "==> {} <==\n{}",
+                outcome.file,
+                updated.strip_suffix('\n').unwrap_or(updated)
+            ),
+            Err(e) => {
+                codes.push(exit_code(e));
+                eprintln!("❌ {}: {:#}", outcome.file, e);
+            }
+        }
+    }
+    let failed = codes.len();
+
+    println!(
+        "{} file(s) succeeded, {} failed",
+        outcomes.len() - failed,
+        failed
+    );
+    if failed > 0 {
+        // Unmet guards only exit with 3; any other failure takes precedence
+        let code = codes.iter().copied().find(|&code| code != 3).unwrap_or(3);
+        return Err(anyhow::Error::new(ExitStatus(code)).context(format!(
+            "{} of {} files failed",
+            failed,
+            outcomes.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Define sort command CLI structure
+pub fn sort_command() -> Command {
+    Command::new("sort")
+        .about("Sort object keys or array items in JSON files")
+        .arg(
+            Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .help("JSON file path ('-' for stdin)")
+                .default_value("package.json"),
+        )
+        .arg(
+            Arg::new("field")
+                .short('k')
+                .long("field")
+                .value_name("FIELD")
+                .help(
+                    "Dot-separated path to the object or array to sort (default: whole document)",
+                ),
+        )
+        .arg(
+            Arg::new("order")
+                .long("order")
+                .value_name("ORDER")
+                .help("Key order for objects")
+                .value_parser(["alpha", "package-json"])
+                .default_value("alpha"),
+        )
+        .arg(
+            Arg::new("keys")
+                .long("keys")
+                .value_name("KEYS")
+                .help("Custom comma-separated key order; other keys follow alphabetically")
+                .value_delimiter(',')
+                .conflicts_with("order"),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help("Also sort keys of nested objects alphabetically")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("by")
+                .long("by")
+                .value_name("KEY")
+                .help("Sort an array of objects by the value at this key path"),
+        )
+        .arg(
+            Arg::new("reverse")
+                .long("reverse")
+                .help("Reverse the sort order")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(format_arg())
+        .arg(
+            Arg::new("jsonc")
+                .long("jsonc")
+                .help("Parse input as JSONC and keep comments when writing; auto-detected for tsconfig.json etc.")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("format"),
+        )
+        .arg(
+            Arg::new("in-place")
+                .short('i')
+                .long("in-place")
+                .help("Modify the file in place")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Handle sort command logic
+pub fn handle_sort_command(matches: &clap::ArgMatches) -> Result<()> {
+    let file_path = matches.get_one::<String>("file").unwrap();
 
-    // Handle value type (auto-detect or specified type)
-    let value_type = if value_type == "auto" {
-        None
+    let key_order = if let Some(keys) = matches.get_many::<String>("keys") {
+        KeyOrder::Custom(keys.cloned().collect())
+    } else if matches.get_one::<String>("order").map(String::as_str) == Some("package-json") {
+        KeyOrder::PackageJson
     } else {
-        Some(value_type.as_str())
+        KeyOrder::Alphabetical
     };
 
-    // Build configuration
-    let config = SetConfig {
+    let config = SortConfig {
         file_path: file_path.to_string(),
-        field_path: field_path.to_string(),
-        value: value.to_string(),
-        value_type: value_type.map(|s| s.to_string()),
-        create_missing,
+        field_path: matches.get_one::<String>("field").cloned(),
+        key_order,
+        recursive: matches.get_flag("recursive"),
+        by: matches.get_one::<String>("by").cloned(),
+        reverse: matches.get_flag("reverse"),
+        format: selected_format(matches)?,
     };
 
-    // Execute set operation
-    if in_place {
-        // Modify file in place
-        set_field_and_save(&config)?;
+    if matches.get_flag("in-place") && file_path != STDIO_PATH {
+        sort_field_and_save(&config)?;
         println!(
-            "✅ Field '{}' set to '{}' in {}",
-            field_path, value, file_path
+            "✅ Sorted '{}' in {}",
+            config.field_path.as_deref().unwrap_or("."),
+            file_path
         );
     } else {
-        // Output modified content (don't modify original file)
-        let result = set_field(&config)?;
-        println!("{}", result);
+        let sorted = sort_field(&config)?;
+        println!("{}", sorted.strip_suffix('\n').unwrap_or(&sorted));
     }
 
     Ok(())
@@ -0,0 +1,60 @@
+//! Reading input documents and writing results
+//!
+//! The path `-` stands for standard input when reading and standard output
+//! when writing, so every command can be used in a pipeline.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::io::{Read, Write};
+
+use crate::error::JsonExtractError;
+
+/// Path that selects standard input/output instead of a file
+pub const STDIO_PATH: &str = "-";
+
+/// Name used in error messages for JSON passed directly as text
+pub const INLINE_SOURCE: &str = "<input>";
+
+/// Read the whole content of a file, or of standard input for `-`
+pub fn read_source(path: &str) -> Result<String> {
+    if path == STDIO_PATH {
+        read_from(std::io::stdin().lock()).context("Failed to read from stdin")
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path))
+    }
+}
+
+/// Read the whole content of any reader as UTF-8 text
+pub fn read_from(mut reader: impl Read) -> std::io::Result<String> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Write content to a file, or to standard output for `-`
+pub fn write_output(path: &str, content: &str) -> Result<()> {
+    if path == STDIO_PATH {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", content).context("Failed to write to stdout")
+    } else {
+        fs::write(path, content).with_context(|| format!("Failed to write to file: {}", path))
+    }
+}
+
+/// Parse JSON text, reporting `source` (a file path or `-`) on syntax errors
+pub fn parse_json(content: &str, source: &str) -> Result<JsonValue, JsonExtractError> {
+    serde_json::from_str(content).map_err(|e| JsonExtractError::InvalidJson {
+        file: display_name(source).to_string(),
+        error: e.to_string(),
+    })
+}
+
+/// Human-readable name of a source path (`<stdin>` for `-`)
+pub fn display_name(path: &str) -> &str {
+    if path == STDIO_PATH {
+        "<stdin>"
+    } else {
+        path
+    }
+}